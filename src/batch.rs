@@ -0,0 +1,181 @@
+use crate::directory::DirectoryOptions;
+use crate::puzzle::{Puzzle, Report};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+/// A puzzle of a batch with its name, or why it could not be read.
+pub type BatchEntry = (String, Result<Puzzle, String>);
+
+/// The outcome of one puzzle of a batch, written as one line of JSON by the CLI.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct BatchResult {
+    /// The puzzle file, or the JSONL file and line number, like `puzzles.jsonl:3`.
+    pub name: String,
+    #[serde(flatten)]
+    pub report: Report,
+    /// Time spent solving, in milliseconds.
+    pub millis: f64,
+    /// Nodes visited by the search, 0 when a quick proof or a read error ended it.
+    pub nodes: usize,
+}
+
+impl BatchResult {
+    /// Solve one puzzle, turning a read error into an unsolved result.
+    pub fn solve(name: &str, puzzle: &Result<Puzzle, String>) -> Self {
+        let nodes = AtomicUsize::new(0);
+        let start = Instant::now();
+        let tiled = puzzle
+            .as_ref()
+            .map_err(|e| e.clone())
+            .and_then(|puzzle| puzzle.solve_counting(&nodes));
+        Self {
+            name: name.to_string(),
+            report: Report::new(&tiled),
+            millis: start.elapsed().as_secs_f64() * 1000.0,
+            nodes: nodes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Puzzles of a directory of puzzle files, read in order of their paths, or of a
+/// JSONL file with one puzzle per line. Piece paths are relative to the directory
+/// of the file. A puzzle which can not be read is kept as an error, so that the
+/// others can still be solved; only an unreadable `path` fails as a whole.
+pub fn read_batch<P>(path: P) -> Result<Vec<BatchEntry>, String>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    if path.is_dir() {
+        let options = DirectoryOptions {
+            include: vec!["json".to_string()],
+            ..Default::default()
+        };
+        return Ok(options
+            .files(path)?
+            .into_iter()
+            .map(|file| (file.display().to_string(), Puzzle::from_path(&file)))
+            .collect());
+    }
+    let buf = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    Ok(buf
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            (
+                format!("{}:{}", path.display(), i + 1),
+                Puzzle::from_json_str(line, base),
+            )
+        })
+        .collect())
+}
+
+/// Solve every puzzle in parallel on the global rayon pool, which the searches of
+/// the puzzles share. The results are in the order of `entries`.
+///
+/// ```
+/// use tiling_mino_solver::batch::{read_batch, solve_batch};
+/// let results = solve_batch(&read_batch("data").unwrap());
+/// assert!(results.iter().any(|r| r.name.ends_with("puzzle.json") && r.report.solved));
+/// ```
+pub fn solve_batch(entries: &[BatchEntry]) -> Vec<BatchResult> {
+    entries
+        .par_iter()
+        .map(|(name, puzzle)| BatchResult::solve(name, puzzle))
+        .collect()
+}
+
+/// Solve every puzzle like [`solve_batch`], writing each result to `out` as one
+/// line of JSON as soon as it is found, so the lines are not in the order of
+/// `entries`. Returns how many puzzles were solved.
+pub fn write_batch<W>(entries: &[BatchEntry], out: W) -> Result<usize, String>
+where
+    W: Write + Send,
+{
+    let out = Mutex::new(out);
+    let solved = AtomicUsize::new(0);
+    entries.par_iter().try_for_each(|(name, puzzle)| {
+        let result = BatchResult::solve(name, puzzle);
+        if result.report.solved {
+            solved.fetch_add(1, Ordering::Relaxed);
+        }
+        let line = serde_json::to_string(&result).map_err(|e| format!("{}: {}", name, e))?;
+        let mut out = out.lock().map_err(|e| e.to_string())?;
+        writeln!(out, "{}", line).map_err(|e| e.to_string())
+    })?;
+    Ok(solved.load(Ordering::Relaxed))
+}
+
+#[test]
+fn test_solve_batch_jsonl() {
+    let dir = std::env::temp_dir().join(format!("batch-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let lines = [
+        r#"{"board": ["...", "..."], "pieces": {"lines": ["2", "aaa"]}}"#,
+        "",
+        r#"{"board": ["..."], "pieces": {"lines": ["1", "aa"]}}"#,
+        "not a puzzle",
+        r#"{"board": ["...."], "pieces": {"lines": ["*", "aa"]}, "rules": {"mode": "supply"}}"#,
+    ];
+    fs::write(dir.join("puzzles.jsonl"), lines.join("\n")).unwrap();
+    let entries = read_batch(dir.join("puzzles.jsonl")).unwrap();
+    let names: Vec<&str> = entries
+        .iter()
+        .map(|(name, _)| name.rsplit(':').next().unwrap())
+        .collect();
+    assert_eq!(names, vec!["1", "3", "4", "5"]);
+    let results = solve_batch(&entries);
+    let solved: Vec<bool> = results.iter().map(|r| r.report.solved).collect();
+    assert_eq!(solved, vec![true, false, false, true]);
+    assert!(results[0].nodes > 0);
+    assert_eq!(results[1].nodes, 0);
+    assert!(
+        results[1]
+            .report
+            .reason
+            .as_ref()
+            .unwrap()
+            .starts_with("Area")
+    );
+    assert!(results[2].report.reason.is_some());
+    assert_eq!(results[3].report.board, Some(vec!["aaaa".to_string()]));
+    let line = serde_json::to_string(&results[0]).unwrap();
+    assert!(line.contains(r#""solved":true"#) && line.contains(r#""nodes":"#));
+    assert!(read_batch(dir.join("missing.jsonl")).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_write_batch_continues_past_failures() {
+    let base = Path::new("");
+    let entries: Vec<BatchEntry> = [
+        r#"{"board": ["..."], "pieces": {"lines": ["1", "a"]}, "rules": {"mode": "subset"}}"#,
+        r#"{"board": ["...."], "pieces": {"lines": ["2", "aa"]}}"#,
+        r#"{"board": [".."], "pieces": {"lines": ["1", "bb"]}, "rules": {"mode": "subset"}}"#,
+    ]
+    .iter()
+    .enumerate()
+    .map(|(i, line)| (i.to_string(), Puzzle::from_json_str(line, base)))
+    .collect();
+    let mut out = vec![];
+    assert_eq!(write_batch(&entries, &mut out).unwrap(), 2);
+    let mut lines: Vec<serde_json::Value> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    lines.sort_by_key(|line| line["name"].as_str().unwrap().to_string());
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0]["solved"], false);
+    assert!(lines[0]["reason"].as_str().unwrap().starts_with("Area"));
+    assert_eq!(lines[1]["solved"], true);
+    assert_eq!(lines[2]["solved"], true);
+}
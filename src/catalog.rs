@@ -0,0 +1,265 @@
+use crate::polyomino::{Equivalence, generate_minos};
+use crate::{Board, Mino, Orientation};
+use std::str::FromStr;
+
+/// Names of the built-in piece sets, for [`catalog`].
+pub const NAMES: [&str; 4] = ["pentominoes", "tetrominoes", "hexominoes", "calendar"];
+
+const PENTOMINOES: &str = "1
+.FF
+FF.
+.F.
+------------
+1
+IIIII
+------------
+1
+L.
+L.
+L.
+LL
+------------
+1
+.N
+NN
+N.
+N.
+------------
+1
+PP
+PP
+P.
+------------
+1
+TTT
+.T.
+.T.
+------------
+1
+U.U
+UUU
+------------
+1
+V..
+V..
+VVV
+------------
+1
+W..
+WW.
+.WW
+------------
+1
+.X.
+XXX
+.X.
+------------
+1
+.Y
+YY
+.Y
+.Y
+------------
+1
+ZZ.
+.Z.
+.ZZ";
+
+const TETROMINOES: &str = "1
+IIII
+------------
+1
+OO
+OO
+------------
+1
+TTT
+.T.
+------------
+1
+.SS
+SS.
+------------
+1
+ZZ.
+.ZZ
+------------
+1
+J..
+JJJ
+------------
+1
+..L
+LLL";
+
+const CALENDAR: &str = "1
+RRR
+RRR
+------------
+1
+L...
+LLLL
+------------
+1
+NN..
+.NNN
+------------
+1
+PP
+PP
+P.
+------------
+1
+U.U
+UUU
+------------
+1
+V..
+V..
+VVV
+------------
+1
+..Y.
+YYYY
+------------
+1
+ZZ.
+.Z.
+.ZZ";
+
+/// The calendar board: months in the first two rows, then days 1 to 31.
+const CALENDAR_BOARD: &str = "......#
+......#
+.......
+.......
+.......
+.......
+...####";
+
+fn parse(text: &str) -> Vec<Mino> {
+    Mino::minos_from_lines(text.lines().map(|s| s.to_string()).collect())
+        .expect("built-in minos are valid")
+}
+
+/// Let the minos be turned over as well as rotated.
+fn free(minos: Vec<Mino>) -> Vec<Mino> {
+    minos
+        .into_iter()
+        .map(|mut mino| {
+            mino.constraints.orientations = Some(Orientation::all());
+            mino
+        })
+        .collect()
+}
+
+/// The 12 free pentominoes, named F I L N P T U V W X Y Z.
+pub fn pentominoes() -> Vec<Mino> {
+    free(parse(PENTOMINOES))
+}
+
+/// The 7 one-sided tetrominoes, named I O T S Z J L.
+pub fn tetrominoes() -> Vec<Mino> {
+    parse(TETROMINOES)
+}
+
+/// The 35 free hexominoes, named 'a' to 'z' and then 'A' to 'I'.
+pub fn hexominoes() -> Vec<Mino> {
+    generate_minos(6, Equivalence::Free).expect("35 hexominoes have names")
+}
+
+/// The pieces of the calendar puzzle: a 2x3 rectangle and the
+/// L N P U V Y Z pentominoes, which may be turned over.
+pub fn calendar() -> Vec<Mino> {
+    free(parse(CALENDAR))
+}
+
+/// The calendar board with the cells of `month` (1 to 12) and `day` (1 to 31) walled off.
+///
+/// ```
+/// use tiling_mino_solver::catalog::{calendar, calendar_board};
+/// let board = calendar_board(10, 18).unwrap();
+/// assert!(board.tile_subset(&calendar()).is_some());
+/// ```
+pub fn calendar_board(month: usize, day: usize) -> Result<Board, String> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(format!("Invalid date {}/{}", month, day));
+    }
+    let mut board = Board::from_str(CALENDAR_BOARD)?;
+    board.shape.toggle((month - 1) % 6, (month - 1) / 6);
+    board.shape.toggle((day - 1) % 7, 2 + (day - 1) / 7);
+    Ok(board)
+}
+
+/// A built-in piece set by one of [`NAMES`].
+///
+/// ```
+/// let minos = tiling_mino_solver::catalog::catalog("pentominoes").unwrap();
+/// let names: String = minos.iter().map(|m| m.name).collect();
+/// assert_eq!(names, "FILNPTUVWXYZ");
+/// ```
+pub fn catalog(name: &str) -> Result<Vec<Mino>, String> {
+    match name {
+        "pentominoes" => Ok(pentominoes()),
+        "tetrominoes" => Ok(tetrominoes()),
+        "hexominoes" => Ok(hexominoes()),
+        "calendar" => Ok(calendar()),
+        _ => Err(format!(
+            "Unknown piece set '{}', expected one of {}",
+            name,
+            NAMES.join(", ")
+        )),
+    }
+}
+
+#[test]
+fn test_catalog_sizes() {
+    let sizes = |minos: Vec<Mino>| -> Vec<usize> { minos.iter().map(|m| m.count_wall()).collect() };
+    assert_eq!(sizes(pentominoes()), vec![5; 12]);
+    assert_eq!(sizes(tetrominoes()), vec![4; 7]);
+    assert_eq!(sizes(hexominoes()), vec![6; 35]);
+    assert_eq!(sizes(calendar()), vec![6, 5, 5, 5, 5, 5, 5, 5]);
+    assert!(catalog("heptominoes").is_err());
+}
+
+#[test]
+fn test_catalog_matches_generated_pieces() {
+    use std::collections::BTreeSet;
+    let canonical = |minos: Vec<Mino>| -> BTreeSet<Vec<Vec<bool>>> {
+        minos
+            .iter()
+            .map(|m| {
+                m.orientations()
+                    .iter()
+                    .map(|o| m.oriented(o).shape.0)
+                    .min()
+                    .unwrap()
+            })
+            .collect()
+    };
+    assert_eq!(
+        canonical(pentominoes()),
+        canonical(generate_minos(5, Equivalence::Free).unwrap())
+    );
+    assert_eq!(
+        canonical(tetrominoes()),
+        canonical(generate_minos(4, Equivalence::OneSided).unwrap())
+    );
+}
+
+#[test]
+fn test_pentominoes_tile_rectangle() {
+    let board = Board::from_str(".....\n.....\n.....").unwrap();
+    let tiled = board.tile_subset(&pentominoes()).unwrap();
+    assert_eq!(tiled.mino_transforms().len(), 3);
+}
+
+#[test]
+fn test_calendar_board() {
+    let board = calendar_board(12, 31).unwrap();
+    assert_eq!(
+        board.pretty_shape(),
+        "......#\n.....##\n.......\n.......\n.......\n.......\n..#####"
+    );
+    assert_eq!(board.shape.count_vacant(), 41);
+    assert!(calendar_board(13, 1).is_err());
+}
@@ -0,0 +1,197 @@
+use crate::gui::Scale;
+use crate::{Rotation, flip_grid, rotate_grid};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Colours printed on the cells of a mino, or target colours of board cells.
+/// `None` where a cell has no colour.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord, Default)]
+pub struct Colors(Vec<Vec<Option<char>>>);
+
+impl Colors {
+    pub fn new(vec: Vec<Vec<Option<char>>>) -> Self {
+        Self(vec)
+    }
+    pub fn width(&self) -> usize {
+        self.0.first().map_or(0, |row| row.len())
+    }
+    pub fn height(&self) -> usize {
+        self.0.len()
+    }
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        self.0.get(y).and_then(|row| row.get(x)).copied().flatten()
+    }
+    /// Inverse of [`Colors::from_str`].
+    pub fn to_lines(&self) -> Vec<String> {
+        self.0
+            .iter()
+            .map(|row| row.iter().map(|c| c.unwrap_or('.')).collect())
+            .collect()
+    }
+    pub(crate) fn rotated(&self, rotation: &Rotation) -> Self {
+        Self(rotate_grid(&self.0, rotation))
+    }
+    pub(crate) fn flipped(&self) -> Self {
+        Self(flip_grid(&self.0))
+    }
+}
+
+/// New cells have no colour; blank rows and columns have no coloured cell.
+impl Scale for Colors {
+    fn width(&self) -> usize {
+        self.width()
+    }
+    fn height(&self) -> usize {
+        self.height()
+    }
+    fn insert_row(&mut self, y: usize) {
+        let width = self.width();
+        self.0.insert(y, vec![None; width]);
+    }
+    fn remove_row(&mut self, y: usize) {
+        self.0.remove(y);
+    }
+    fn insert_column(&mut self, x: usize) {
+        self.0.iter_mut().for_each(|row| row.insert(x, None));
+    }
+    fn remove_column(&mut self, x: usize) {
+        self.0.iter_mut().for_each(|row| {
+            row.remove(x);
+        });
+    }
+    fn is_blank_row(&self, y: usize) -> bool {
+        self.0[y].iter().all(Option::is_none)
+    }
+    fn is_blank_column(&self, x: usize) -> bool {
+        self.0.iter().all(|row| row[x].is_none())
+    }
+}
+
+/// Rows of colour characters, where '.' and '#' are cells without a colour.
+///
+/// ```
+/// use std::str::FromStr;
+/// use tiling_mino_solver::colors::Colors;
+/// let colors = Colors::from_str("BW\n.B").unwrap();
+/// assert_eq!(colors.get(1, 0), Some('W'));
+/// assert_eq!(colors.get(0, 1), None);
+/// ```
+impl FromStr for Colors {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(
+            s.lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    line.chars()
+                        .map(|c| if c == '.' || c == '#' { None } else { Some(c) })
+                        .collect()
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Split text at a line of a single '=' into the grid above it and the colours below.
+pub(crate) fn split_colors(s: &str) -> (String, Option<String>) {
+    let lines: Vec<&str> = s.lines().collect();
+    match lines.iter().position(|line| line.trim() == "=") {
+        Some(i) => (lines[..i].join("\n"), Some(lines[i + 1..].join("\n"))),
+        None => (s.to_string(), None),
+    }
+}
+
+#[test]
+fn test_colors_rotated_and_flipped() {
+    let colors = Colors::from_str("BW.\n..B").unwrap();
+    assert_eq!(
+        colors.rotated(&Rotation::Left),
+        Colors::from_str(".B\nW.\nB.").unwrap()
+    );
+    assert_eq!(colors.flipped(), Colors::from_str(".WB\nB..").unwrap());
+}
+
+#[test]
+fn test_mino_from_str_with_colors() {
+    use crate::Mino;
+    let mino = Mino::from_str("aa\n.a\n=\nBW\n.B").unwrap();
+    assert_eq!(mino.shape, Mino::from_str("aa\n.a").unwrap().shape);
+    assert_eq!(mino.colors, Some(Colors::from_str("BW\n.B").unwrap()));
+    assert!(Mino::from_str("aa\n.a\n=\nBW\nBB").is_err());
+}
+
+#[test]
+fn test_can_put_matches_colors() {
+    use crate::{Board, Mino, Rotation, TransForm};
+    // Checkerboard target with a domino printed black and white.
+    let board = Board::from_str("...\n...\n=\nBWB\nWBW").unwrap();
+    let mino = Mino::from_str("aa\n=\nBW").unwrap();
+    let at = |x, y, rotation, flipped| TransForm {
+        x,
+        y,
+        rotation,
+        flipped,
+    };
+    assert!(board.can_put(&mino, &at(0, 0, Rotation::Neutral, false)));
+    assert!(!board.can_put(&mino, &at(1, 0, Rotation::Neutral, false)));
+    assert!(board.can_put(&mino, &at(1, 0, Rotation::OneEighty, false)));
+    assert!(board.can_put(&mino, &at(1, 0, Rotation::Neutral, true)));
+    assert!(board.can_put(&mino, &at(0, 0, Rotation::Right, false)));
+    assert!(!board.can_put(&mino, &at(0, 0, Rotation::Left, false)));
+    // Two dominoes only tile the 2x2 checkerboard in the matching orientations.
+    let board = Board::from_str("..\n..\n=\nBW\nWB").unwrap();
+    let minos = vec![mino.clone(), mino];
+    let tiled = board.tile_serial(&minos).unwrap();
+    let rotations: Vec<Rotation> = tiled
+        .mino_transforms()
+        .iter()
+        .map(|(_, t)| t.rotation.clone())
+        .collect();
+    assert!(
+        rotations == vec![Rotation::Neutral, Rotation::OneEighty]
+            || rotations == vec![Rotation::Right, Rotation::Left]
+    );
+}
+
+#[test]
+fn test_board_from_str_with_colored_minos() {
+    use crate::{Board, Mino};
+    let minos = vec![Mino::from_str("aa\n=\nBW").unwrap()];
+    let board = Board::from_str_with_minos("..\naa\n=\nBW\nWB", &minos).unwrap();
+    assert_eq!(
+        board.fixed_mino_transforms()[0].1.rotation,
+        crate::Rotation::OneEighty
+    );
+    assert_eq!(
+        Board::from_str_with_minos("..\naa\n=\nBW\nWW", &minos).unwrap_err(),
+        "Cells of 'a' at (0, 1) do not match the target colors"
+    );
+}
+
+#[test]
+fn test_double_sided_mino() {
+    use crate::{Board, Mino, Rotation, TransForm};
+    let mino = Mino::from_str("a.\naa\n=\nB.\nBW\n=\nW.\nWW").unwrap();
+    assert_eq!(mino.back_colors, Some(Colors::from_str("W.\nWW").unwrap()));
+    assert_eq!(mino.orientations().len(), 8);
+    let flipped = mino.flipped();
+    assert_eq!(flipped.shape, Mino::from_str(".a\naa").unwrap().shape);
+    assert_eq!(flipped.colors, Some(Colors::from_str(".W\nWW").unwrap()));
+    assert_eq!(
+        flipped.back_colors,
+        Some(Colors::from_str(".B\nWB").unwrap())
+    );
+    assert_eq!(flipped.flipped(), mino);
+    // Only the back face is all white.
+    let board = Board::from_str(".#\n..\n=\nW#\nWW").unwrap();
+    let at = |flipped| TransForm {
+        x: 0,
+        y: 0,
+        rotation: Rotation::Right,
+        flipped,
+    };
+    assert!(!board.can_put(&mino, &at(false)));
+    assert!(board.can_put(&mino, &at(true)));
+    let tiled = board.tile_serial(&[mino]).unwrap();
+    assert!(tiled.mino_transforms()[0].1.flipped);
+}
@@ -0,0 +1,206 @@
+use crate::{Board, Orientation};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Where and how a mino may be put on a board.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord, Default)]
+pub struct Constraints {
+    /// Allowed orientations. `None` leaves them to [`crate::Mino::orientations`].
+    #[serde(default)]
+    pub orientations: Option<Vec<Orientation>>,
+    /// Board cells the mino must not cover.
+    #[serde(default)]
+    pub forbidden_cells: Vec<(usize, usize)>,
+    /// Board cells the mino must cover.
+    #[serde(default)]
+    pub required_cells: Vec<(usize, usize)>,
+    /// Whether the mino must cover a cell on an edge of the board.
+    #[serde(default)]
+    pub touch_border: bool,
+}
+
+impl Constraints {
+    /// Whether a mino covering `cells` of `board` keeps the constraints.
+    pub fn allows(&self, board: &Board, cells: &[(usize, usize)]) -> bool {
+        let on_border = |&(x, y): &(usize, usize)| {
+            (!board.topology().wraps_x() && (x == 0 || x + 1 == board.width()))
+                || (!board.topology().wraps_y() && (y == 0 || y + 1 == board.height()))
+        };
+        !cells.iter().any(|cell| self.forbidden_cells.contains(cell))
+            && self.required_cells.iter().all(|cell| cells.contains(cell))
+            && (!self.touch_border || cells.iter().any(on_border))
+    }
+    /// Inverse of [`Constraints::read_line`] for each constraint which is set.
+    pub fn to_lines(&self) -> Vec<String> {
+        let cells = |cells: &[(usize, usize)]| {
+            cells
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        let mut lines = vec![];
+        if let Some(orientations) = &self.orientations {
+            if *orientations == Orientation::all() {
+                lines.push("orientations: all".to_string());
+            } else {
+                let words: Vec<String> = orientations.iter().map(|o| o.to_string()).collect();
+                lines.push(format!("orientations: {}", words.join(" ")));
+            }
+        }
+        if !self.forbidden_cells.is_empty() {
+            lines.push(format!("forbidden: {}", cells(&self.forbidden_cells)));
+        }
+        if !self.required_cells.is_empty() {
+            lines.push(format!("required: {}", cells(&self.required_cells)));
+        }
+        if self.touch_border {
+            lines.push("border: true".to_string());
+        }
+        lines
+    }
+    /// Read a `key: value` line of a minos block:
+    ///
+    /// - `orientations: left right flipped_neutral`, or `all` for every rotation and flip
+    /// - `forbidden: 0,0 3,1` for cells which must not be covered
+    /// - `required: 2,2` for cells which must be covered
+    /// - `border: true` when an edge of the board must be covered
+    ///
+    /// ```
+    /// use tiling_mino_solver::constraints::Constraints;
+    /// let mut constraints = Constraints::default();
+    /// constraints.read_line("forbidden: 0,0 3,1").unwrap();
+    /// constraints.read_line("orientations: all").unwrap();
+    /// assert_eq!(constraints.forbidden_cells, vec![(0, 0), (3, 1)]);
+    /// assert_eq!(constraints.orientations.unwrap().len(), 8);
+    /// ```
+    pub fn read_line(&mut self, line: &str) -> Result<(), String> {
+        let (key, value) = line
+            .split_once(':')
+            .ok_or(format!("Invalid constraint '{}'", line))?;
+        match key.trim() {
+            "orientations" => {
+                let mut orientations = vec![];
+                for word in value.split_whitespace() {
+                    match word {
+                        "all" => orientations.extend(Orientation::all()),
+                        "rotations" => orientations.extend(Orientation::rotations()),
+                        _ => orientations.push(Orientation::from_str(word)?),
+                    }
+                }
+                self.orientations = Some(orientations);
+            }
+            "forbidden" => self.forbidden_cells.extend(parse_cells(value)?),
+            "required" => self.required_cells.extend(parse_cells(value)?),
+            "border" => {
+                self.touch_border = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid border constraint '{}'", value.trim()))?
+            }
+            _ => return Err(format!("Unknown constraint '{}'", key.trim())),
+        }
+        Ok(())
+    }
+}
+
+/// Parse space separated `x,y` cells.
+fn parse_cells(s: &str) -> Result<Vec<(usize, usize)>, String> {
+    s.split_whitespace()
+        .map(|cell| {
+            let (x, y) = cell
+                .split_once(',')
+                .ok_or(format!("Invalid cell '{}'", cell))?;
+            let parse = |n: &str| {
+                n.parse::<usize>()
+                    .map_err(|e| format!("Invalid cell '{}': {}", cell, e))
+            };
+            Ok((parse(x)?, parse(y)?))
+        })
+        .collect()
+}
+
+#[test]
+fn test_constraints_allows() {
+    let board = Board::from_str("...\n...\n...").unwrap();
+    let constraints = Constraints {
+        orientations: None,
+        forbidden_cells: vec![(1, 1)],
+        required_cells: vec![(0, 2)],
+        touch_border: false,
+    };
+    assert!(constraints.allows(&board, &[(0, 2), (1, 2)]));
+    assert!(!constraints.allows(&board, &[(0, 2), (1, 1)]));
+    assert!(!constraints.allows(&board, &[(1, 2), (2, 2)]));
+    let border = Constraints {
+        touch_border: true,
+        ..Default::default()
+    };
+    assert!(border.allows(&board, &[(1, 0)]));
+    assert!(!border.allows(&board, &[(1, 1)]));
+}
+
+#[test]
+fn test_constraints_read_line_when_invalid() {
+    let mut constraints = Constraints::default();
+    assert!(constraints.read_line("color: red").is_err());
+    assert!(constraints.read_line("forbidden: 1;2").is_err());
+    assert!(constraints.read_line("orientations: up").is_err());
+    assert!(constraints.read_line("border: maybe").is_err());
+}
+
+#[test]
+fn test_search_can_put_with_constraints() {
+    use crate::{Mino, Rotation};
+    let board = Board::from_str("....\n....").unwrap();
+    let mut mino = Mino::from_str("a.\naa").unwrap();
+    assert_eq!(board.search_can_put(&mino).len(), 12);
+    mino.constraints.orientations = Some(vec![Orientation {
+        rotation: Rotation::Neutral,
+        flipped: false,
+    }]);
+    assert_eq!(board.search_can_put(&mino).len(), 3);
+    mino.constraints.required_cells = vec![(3, 1)];
+    let ts = board.search_can_put(&mino);
+    assert_eq!(ts.len(), 1);
+    assert_eq!((ts[0].x, ts[0].y), (2, 0));
+    mino.constraints.required_cells = vec![];
+    mino.constraints.forbidden_cells = vec![(1, 1), (2, 1)];
+    assert!(board.search_can_put(&mino).is_empty());
+}
+
+#[test]
+fn test_minos_from_lines_with_constraints() {
+    use crate::Mino;
+    let lines = vec![
+        "1",
+        "orientations: neutral flipped_neutral",
+        "required: 0,0",
+        "a.",
+        "aa",
+        "------------",
+        "1",
+        "border: true",
+        "bbb",
+    ];
+    let minos = Mino::minos_from_lines(lines.iter().map(|s| s.to_string()).collect()).unwrap();
+    assert_eq!(minos[0].shape, Mino::from_str("a.\naa").unwrap().shape);
+    assert_eq!(minos[0].orientations().len(), 2);
+    assert_eq!(minos[0].constraints.required_cells, vec![(0, 0)]);
+    assert!(minos[1].constraints.touch_border);
+}
+
+#[test]
+fn test_constraints_to_lines() {
+    let mut constraints = Constraints::default();
+    let lines = [
+        "orientations: neutral flipped_one_eighty",
+        "forbidden: 0,0 3,1",
+        "required: 2,2",
+        "border: true",
+    ];
+    for line in lines {
+        constraints.read_line(line).unwrap();
+    }
+    assert_eq!(constraints.to_lines(), lines);
+}
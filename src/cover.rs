@@ -0,0 +1,444 @@
+use crate::{Board, Mino, TransForm};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// How many copies of a mino kind a cover may use.
+/// `max` is `None` for an unlimited supply.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Supply {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Supply {
+    pub fn exactly(count: usize) -> Self {
+        Self {
+            min: count,
+            max: Some(count),
+        }
+    }
+    pub fn at_most(count: usize) -> Self {
+        Self {
+            min: 0,
+            max: Some(count),
+        }
+    }
+    pub fn unlimited() -> Self {
+        Self { min: 0, max: None }
+    }
+    /// The count when the supply is an exact number of copies.
+    pub fn exact_count(&self) -> Option<usize> {
+        self.max.filter(|&max| max == self.min)
+    }
+    fn merge(self, other: Self) -> Self {
+        Self {
+            min: self.min + other.min,
+            max: self.max.zip(other.max).map(|(a, b)| a + b),
+        }
+    }
+}
+
+/// Parse the count line of a minos block: `3` for exactly three copies,
+/// `1..3`, `2..` or `..3` for a range, and `*` for any number of copies.
+///
+/// ```
+/// use tiling_mino_solver::cover::Supply;
+/// assert_eq!("3".parse(), Ok(Supply::exactly(3)));
+/// assert_eq!("..3".parse(), Ok(Supply::at_most(3)));
+/// assert_eq!("*".parse(), Ok(Supply::unlimited()));
+/// assert_eq!("2..".parse(), Ok(Supply { min: 2, max: None }));
+/// ```
+impl FromStr for Supply {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parse = |n: &str| {
+            n.parse::<usize>()
+                .map_err(|e| format!("Invalid mino count '{}': {}", s, e))
+        };
+        if s == "*" {
+            return Ok(Self::unlimited());
+        }
+        let Some((min, max)) = s.split_once("..") else {
+            return Ok(Self::exactly(parse(s)?));
+        };
+        let min = if min.is_empty() { 0 } else { parse(min)? };
+        let max = if max.is_empty() {
+            None
+        } else {
+            Some(parse(max)?)
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(format!("Invalid mino count '{}': empty range", s));
+        }
+        Ok(Self { min, max })
+    }
+}
+
+impl fmt::Display for Supply {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => write!(f, "{}", min),
+            (0, None) => write!(f, "*"),
+            (min, Some(max)) => write!(f, "{}..{}", min, max),
+            (min, None) => write!(f, "{}..", min),
+        }
+    }
+}
+
+/// A mino kind put at a transform, with the board cells it covers.
+#[derive(Clone, Debug)]
+pub(crate) struct Placement {
+    pub kind: usize,
+    pub transform: TransForm,
+    pub cells: Vec<usize>,
+}
+
+/// Cell-first exact cover of the vacant cells of a board.
+/// Identical minos are grouped into kinds so copies are never permuted.
+#[derive(Clone)]
+pub(crate) struct CoverSearch<'a> {
+    board: &'a Board,
+    kinds: Vec<Mino>,
+    supplies: Vec<Supply>,
+    placements: Vec<Placement>,
+    by_cell: Vec<Vec<usize>>,
+    occupied: Vec<bool>,
+    /// Cells which have to be covered. The other vacant cells may stay vacant.
+    required: Vec<bool>,
+    used: Vec<usize>,
+    chosen: Vec<usize>,
+    /// Counts the searched nodes across every branch, when set.
+    nodes: Option<&'a AtomicUsize>,
+    /// Stops the search, finding nothing, once it is set.
+    cancel: Option<&'a AtomicBool>,
+}
+
+impl<'a> CoverSearch<'a> {
+    /// Each of `minos` may be used at most once.
+    pub fn new(board: &'a Board, minos: &[Mino]) -> Self {
+        let supplies: Vec<(Mino, Supply)> = minos
+            .iter()
+            .map(|mino| (mino.clone(), Supply::at_most(1)))
+            .collect();
+        Self::with_supplies(board, &supplies)
+    }
+    pub fn with_supplies(board: &'a Board, mino_supplies: &[(Mino, Supply)]) -> Self {
+        let mut kinds: Vec<Mino> = vec![];
+        let mut supplies: Vec<Supply> = vec![];
+        for (mino, supply) in mino_supplies {
+            if let Some(kind) = kinds.iter().position(|k| k == mino) {
+                supplies[kind] = supplies[kind].merge(*supply);
+            } else {
+                kinds.push(mino.clone());
+                supplies.push(*supply);
+            }
+        }
+        let width = board.width();
+        let mut placements = vec![];
+        for (kind, mino) in kinds.iter().enumerate() {
+            for transform in board.search_can_put(mino) {
+                let cells = board
+                    .covered_cells(&mino.oriented(&transform.orientation()), &transform)
+                    .expect("searched placements are inside the board")
+                    .into_iter()
+                    .map(|(x, y)| y * width + x)
+                    .collect();
+                placements.push(Placement {
+                    kind,
+                    transform,
+                    cells,
+                });
+            }
+        }
+        let mut by_cell = vec![vec![]; width * board.height()];
+        for (index, placement) in placements.iter().enumerate() {
+            for &cell in &placement.cells {
+                by_cell[cell].push(index);
+            }
+        }
+        let occupied: Vec<bool> = board
+            .shape
+            .coordinates()
+            .into_iter()
+            .map(|(_, _, b)| b)
+            .collect();
+        Self {
+            required: vec![true; occupied.len()],
+            board,
+            used: vec![0; kinds.len()],
+            kinds,
+            supplies,
+            placements,
+            by_cell,
+            occupied,
+            chosen: vec![],
+            nodes: None,
+            cancel: None,
+        }
+    }
+    /// Only cover the vacant `cells`, as `(x, y)`, leaving the other cells optional.
+    pub fn with_required_cells(mut self, cells: &[(usize, usize)]) -> Self {
+        let width = self.board.width();
+        self.required = vec![false; self.occupied.len()];
+        for &(x, y) in cells {
+            self.required[y * width + x] = true;
+        }
+        self
+    }
+    /// Add the number of searched nodes to `nodes`.
+    pub fn with_node_counter(mut self, nodes: &'a AtomicUsize) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+    /// Give up, as if there was no cover, once `cancel` is set.
+    pub fn with_cancel(mut self, cancel: &'a AtomicBool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .is_some_and(|cancel| cancel.load(Ordering::Relaxed))
+    }
+    fn first_vacant(&self) -> Option<usize> {
+        (0..self.occupied.len()).find(|&c| self.required[c] && !self.occupied[c])
+    }
+    /// Vacant cells which have to be covered, and all vacant cells.
+    fn n_vacant(&self) -> (usize, usize) {
+        let vacant = (0..self.occupied.len()).filter(|&c| !self.occupied[c]);
+        let required = vacant.clone().filter(|&c| self.required[c]).count();
+        (required, vacant.count())
+    }
+    fn is_available(&self, kind: usize) -> bool {
+        self.supplies[kind]
+            .max
+            .is_none_or(|max| self.used[kind] < max)
+    }
+    /// Area the remaining minos can cover at most, `None` if unlimited.
+    fn available_area(&self) -> Option<usize> {
+        self.kinds
+            .iter()
+            .zip(&self.supplies)
+            .zip(&self.used)
+            .map(|((mino, supply), used)| supply.max.map(|max| mino.count_wall() * (max - used)))
+            .sum()
+    }
+    /// Area the remaining minos have to cover at least.
+    fn required_area(&self) -> usize {
+        self.kinds
+            .iter()
+            .zip(&self.supplies)
+            .zip(&self.used)
+            .map(|((mino, supply), used)| mino.count_wall() * supply.min.saturating_sub(*used))
+            .sum()
+    }
+    /// Placements which can cover `cell` next.
+    fn candidates(&self, cell: usize) -> Vec<usize> {
+        self.by_cell[cell]
+            .iter()
+            .copied()
+            .filter(|&p| {
+                let placement = &self.placements[p];
+                self.is_available(placement.kind)
+                    && placement.cells.iter().all(|&c| !self.occupied[c])
+            })
+            .collect()
+    }
+    fn apply(&mut self, p: usize) {
+        let placement = &self.placements[p];
+        self.used[placement.kind] += 1;
+        for &c in &placement.cells {
+            self.occupied[c] = true;
+        }
+        self.chosen.push(p);
+    }
+    fn revert(&mut self) {
+        let p = self.chosen.pop().expect("a placement to revert");
+        let placement = &self.placements[p];
+        self.used[placement.kind] -= 1;
+        for &c in &placement.cells {
+            self.occupied[c] = false;
+        }
+    }
+    fn search(&mut self) -> bool {
+        if let Some(nodes) = self.nodes {
+            nodes.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.is_cancelled() {
+            return false;
+        }
+        let Some(cell) = self.first_vacant() else {
+            return self.required_area() == 0;
+        };
+        let (n_required, n_vacant) = self.n_vacant();
+        if self.available_area().is_some_and(|area| area < n_required)
+            || self.required_area() > n_vacant
+        {
+            return false;
+        }
+        for p in self.candidates(cell) {
+            self.apply(p);
+            if self.search() {
+                return true;
+            }
+            self.revert();
+        }
+        false
+    }
+    /// Search in parallel over the placements covering the first vacant cell.
+    pub fn solve(&self) -> Option<Self> {
+        if let Some(nodes) = self.nodes {
+            nodes.fetch_add(1, Ordering::Relaxed);
+        }
+        let Some(cell) = self.first_vacant() else {
+            return (self.required_area() == 0).then(|| self.clone());
+        };
+        self.candidates(cell).into_par_iter().find_map_any(|p| {
+            let mut branch = self.clone();
+            branch.apply(p);
+            branch.search().then_some(branch)
+        })
+    }
+    /// The board with every chosen placement put on it.
+    pub fn board(&self) -> Board {
+        let mut board = self.board.clone();
+        for &p in &self.chosen {
+            let placement = &self.placements[p];
+            board.push_mino(
+                self.kinds[placement.kind].clone(),
+                placement.transform.clone(),
+            );
+        }
+        board
+    }
+}
+
+impl Board {
+    /// Cover every vacant cell without overlap, using any subset of `minos`.
+    /// The used minos are recorded in [`Board::mino_transforms`] of the returned board.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino};
+    /// let board = Board::from_str("...\n...").unwrap();
+    /// let minos = vec![
+    ///     Mino::from_str("aa\naa").unwrap(),
+    ///     Mino::from_str("bbb").unwrap(),
+    ///     Mino::from_str("ccc").unwrap(),
+    /// ];
+    /// let tiled = board.tile_subset(&minos).unwrap();
+    /// assert_eq!(tiled.mino_transforms().len(), 2);
+    /// ```
+    pub fn tile_subset(&self, minos: &[Mino]) -> Option<Self> {
+        CoverSearch::new(self, minos).solve().map(|s| s.board())
+    }
+    /// Cover every vacant cell without overlap, using each mino kind as many
+    /// times as its [`Supply`] allows.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino, cover::Supply};
+    /// let board = Board::from_str("......\n......").unwrap();
+    /// let l_tromino = Mino::from_str("a.\naa").unwrap();
+    /// let tiled = board.tile_with_supply(&[(l_tromino, Supply::unlimited())]).unwrap();
+    /// assert_eq!(tiled.mino_transforms().len(), 4);
+    /// ```
+    pub fn tile_with_supply(&self, supplies: &[(Mino, Supply)]) -> Option<Self> {
+        CoverSearch::with_supplies(self, supplies)
+            .solve()
+            .map(|s| s.board())
+    }
+}
+
+#[test]
+fn test_tile_subset_chooses_pieces() {
+    let board = Board::from_str("....\n....").unwrap();
+    let minos = vec![
+        Mino::from_str("aaa\naaa").unwrap(),
+        Mino::from_str("bb\nbb").unwrap(),
+        Mino::from_str("cc\ncc").unwrap(),
+        Mino::from_str("d").unwrap(),
+    ];
+    let tiled = board.tile_subset(&minos).unwrap();
+    let mut used: Vec<char> = tiled
+        .mino_transforms()
+        .iter()
+        .map(|(m, _)| m.name)
+        .collect();
+    used.sort();
+    assert_eq!(used, vec!['b', 'c']);
+    assert_eq!(tiled.shape.count_vacant(), 0);
+}
+
+#[test]
+fn test_tile_subset_with_copies() {
+    let board = Board::from_str("....\n#..#").unwrap();
+    let minos = vec![Mino::from_str("aa").unwrap(); 5];
+    let tiled = board.tile_subset(&minos).unwrap();
+    assert_eq!(tiled.mino_transforms().len(), 3);
+    assert_eq!(tiled.pretty_shape(), "aaaa\n#aa#");
+}
+
+#[test]
+fn test_tile_subset_impossible() {
+    let board = Board::from_str("...\n...").unwrap();
+    let minos = vec![
+        Mino::from_str("aa\naa").unwrap(),
+        Mino::from_str("bbb").unwrap(),
+    ];
+    assert!(board.tile_subset(&minos).is_none());
+}
+
+#[test]
+fn test_tile_with_unlimited_supply() {
+    let board = Board::from_str("...\n...\n...").unwrap();
+    let l_tromino = Mino::from_str("a.\naa").unwrap();
+    let bar = Mino::from_str("bbb").unwrap();
+    assert!(
+        board
+            .tile_with_supply(&[(l_tromino.clone(), Supply::unlimited())])
+            .is_none()
+    );
+    let tiled = board
+        .tile_with_supply(&[(l_tromino, Supply::unlimited()), (bar, Supply::unlimited())])
+        .unwrap();
+    assert_eq!(tiled.shape.count_vacant(), 0);
+}
+
+#[test]
+fn test_tile_with_supply_range() {
+    let board = Board::from_str("....\n....").unwrap();
+    let domino = Mino::from_str("aa").unwrap();
+    let square = Mino::from_str("bb\nbb").unwrap();
+    let supplies = [
+        (domino, Supply { min: 1, max: None }),
+        (square, Supply::at_most(2)),
+    ];
+    let tiled = board.tile_with_supply(&supplies).unwrap();
+    let used = |name| {
+        tiled
+            .mino_transforms()
+            .iter()
+            .filter(|(m, _)| m.name == name)
+            .count()
+    };
+    assert!(used('a') >= 1);
+    assert_eq!(used('a') * 2 + used('b') * 4, 8);
+    assert!(
+        board
+            .tile_with_supply(&[(Mino::from_str("aa").unwrap(), Supply::exactly(3))])
+            .is_none()
+    );
+}
+
+#[test]
+fn test_supply_display() {
+    for s in ["3", "1..3", "2..", "*"] {
+        assert_eq!(Supply::from_str(s).unwrap().to_string(), s);
+    }
+    assert_eq!(Supply::from_str("0..").unwrap().to_string(), "*");
+    assert!(Supply::from_str("3..1").is_err());
+    assert!(Supply::from_str("x").is_err());
+}
@@ -0,0 +1,193 @@
+use crate::cover::CoverSearch;
+use crate::packing::PackObjective;
+use crate::{Board, Mino};
+
+/// Why `minos` can not tile a board, narrowed down by [`Board::diagnose`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnosis {
+    /// A proof by [`Board::infeasibility`], found without searching.
+    pub proof: Option<String>,
+    /// A minimal set of minos which can not even be put on the board together.
+    pub minos: Option<Vec<Mino>>,
+    /// A minimal set of vacant cells, as `(x, y)`, which the minos can not cover
+    /// even when the other vacant cells may stay vacant.
+    pub region: Option<Vec<(usize, usize)>>,
+}
+
+impl Diagnosis {
+    /// A readable report, one finding per line.
+    pub fn to_lines(&self, board: &Board) -> Vec<String> {
+        let mut lines = vec![];
+        if let Some(proof) = &self.proof {
+            lines.push(proof.clone());
+        }
+        if let Some(minos) = &self.minos {
+            let names: String = minos.iter().map(|m| m.name).collect();
+            lines.push(format!(
+                "These minos do not fit on the board together: {}",
+                names
+            ));
+        }
+        if let Some(region) = &self.region {
+            lines.push("These cells (marked *) can not be covered:".to_string());
+            let mut rows = board.char_matrix();
+            for &(x, y) in region {
+                rows[y][x] = '*';
+            }
+            lines.extend(
+                rows.into_iter()
+                    .map(|row| row.into_iter().collect::<String>()),
+            );
+        }
+        if lines.is_empty() {
+            lines.push("No smaller infeasible part was found".to_string());
+        }
+        lines
+    }
+}
+
+/// Drop each item in turn while `infeasible` still holds for the rest,
+/// leaving a set of which no item can be dropped.
+fn shrink<T: Clone>(items: Vec<T>, infeasible: impl Fn(&[T]) -> bool) -> Vec<T> {
+    let mut kept = items;
+    let mut i = 0;
+    while i < kept.len() {
+        let mut rest = kept.clone();
+        rest.remove(i);
+        if infeasible(&rest) {
+            kept = rest;
+        } else {
+            i += 1;
+        }
+    }
+    kept
+}
+
+impl Board {
+    /// A minimal subset of `minos` which can not be put on the board together,
+    /// or `None` when all of them fit.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino};
+    /// let board = Board::from_str("...\n...\n...").unwrap();
+    /// let minos: Vec<Mino> = ["aaaa", "b", "cc\ncc"]
+    ///     .iter()
+    ///     .map(|s| Mino::from_str(s).unwrap())
+    ///     .collect();
+    /// let names: Vec<char> = board.infeasible_minos(&minos).unwrap().iter().map(|m| m.name).collect();
+    /// assert_eq!(names, vec!['a']);
+    /// ```
+    pub fn infeasible_minos(&self, minos: &[Mino]) -> Option<Vec<Mino>> {
+        let fits = |minos: &[Mino]| self.pack(minos, PackObjective::Pieces).unplaced.is_empty();
+        if fits(minos) {
+            return None;
+        }
+        Some(shrink(minos.to_vec(), |rest| !fits(rest)))
+    }
+    /// A minimal set of vacant cells which `minos`, each used at most once,
+    /// can not cover, or `None` when every vacant cell can be covered.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino};
+    /// // No bar can cover the cell right of the wall.
+    /// let board = Board::from_str("...#.").unwrap();
+    /// let minos = vec![Mino::from_str("aaa").unwrap(); 2];
+    /// assert_eq!(board.infeasible_region(&minos), Some(vec![(4, 0)]));
+    /// ```
+    pub fn infeasible_region(&self, minos: &[Mino]) -> Option<Vec<(usize, usize)>> {
+        let covers = |cells: &[(usize, usize)]| {
+            CoverSearch::new(self, minos)
+                .with_required_cells(cells)
+                .solve()
+                .is_some()
+        };
+        let vacant: Vec<(usize, usize)> = self
+            .shape
+            .coordinates()
+            .into_iter()
+            .filter(|&(_, _, b)| !b)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        if covers(&vacant) {
+            return None;
+        }
+        Some(shrink(vacant, |rest| !covers(rest)))
+    }
+    /// Narrow down why `minos` can not tile the board exactly once each:
+    /// a quick proof if there is one, otherwise a minimal set of minos which
+    /// do not fit together and a minimal region which can not be covered.
+    pub fn diagnose(&self, minos: &[Mino]) -> Diagnosis {
+        if let Some(proof) = self.infeasibility(minos) {
+            return Diagnosis {
+                proof: Some(proof),
+                minos: None,
+                region: None,
+            };
+        }
+        Diagnosis {
+            proof: None,
+            minos: self.infeasible_minos(minos),
+            region: self.infeasible_region(minos),
+        }
+    }
+}
+
+#[test]
+fn test_diagnose_minos() {
+    use std::str::FromStr;
+    // The L and J tetrominoes can not both be put on a 3x3 board
+    // without being turned over.
+    let board = Board::from_str("...\n...\n...").unwrap();
+    let minos: Vec<Mino> = ["aaa\na..", "bbb\n..b", "c"]
+        .iter()
+        .map(|s| Mino::from_str(s).unwrap())
+        .collect();
+    assert_eq!(board.infeasibility(&minos), None);
+    assert!(board.tile_parallel(&minos).is_none());
+    let diagnosis = board.diagnose(&minos);
+    assert_eq!(diagnosis.proof, None);
+    let names: Vec<char> = diagnosis.minos.unwrap().iter().map(|m| m.name).collect();
+    assert_eq!(names, vec!['a', 'b']);
+    // Nor can they cover the two lower rows, even with the top row to spare.
+    let diagnosis = Diagnosis {
+        minos: None,
+        ..diagnosis
+    };
+    assert_eq!(
+        diagnosis.to_lines(&board),
+        vec![
+            "These cells (marked *) can not be covered:",
+            "...",
+            "***",
+            "***"
+        ]
+    );
+}
+
+#[test]
+fn test_diagnose_with_proof() {
+    use std::str::FromStr;
+    let board = Board::from_str("..").unwrap();
+    let diagnosis = board.diagnose(&[Mino::from_str("aaa").unwrap()]);
+    assert!(diagnosis.proof.unwrap().starts_with("Area"));
+    assert_eq!(diagnosis.minos, None);
+}
+
+#[test]
+fn test_infeasible_minos_is_minimal() {
+    use std::str::FromStr;
+    let board = Board::from_str("....\n....").unwrap();
+    let minos: Vec<Mino> = ["aaa", "bbb", "ccc", "d"]
+        .iter()
+        .map(|s| Mino::from_str(s).unwrap())
+        .collect();
+    let names: Vec<char> = board
+        .infeasible_minos(&minos)
+        .unwrap()
+        .iter()
+        .map(|m| m.name)
+        .collect();
+    assert_eq!(names, vec!['a', 'b', 'c']);
+}
@@ -0,0 +1,137 @@
+use crate::cover::Supply;
+use crate::json::{is_json, read_json_supplies};
+use crate::{Mino, symmetry};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Which files of a directory [`Mino::supplies_from_directory`] reads.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DirectoryOptions {
+    /// Whether to read subdirectories too.
+    pub recursive: bool,
+    /// Extensions of the files to read, like `txt`. Every file when empty.
+    pub include: Vec<String>,
+    /// Extensions of the files to skip, even when included.
+    pub exclude: Vec<String>,
+}
+
+impl DirectoryOptions {
+    /// Whether the extension of `path` passes the include and exclude lists.
+    fn accepts(&self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let listed = |extensions: &[String]| {
+            extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').to_lowercase() == extension)
+        };
+        (self.include.is_empty() || listed(&self.include)) && !listed(&self.exclude)
+    }
+    /// Files to read under `directory`, sorted by path.
+    pub(crate) fn files(&self, directory: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut entries = directory
+            .read_dir()
+            .map_err(|e| format!("{}: {}", directory.display(), e))?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<PathBuf>, _>>()
+            .map_err(|e| format!("{}: {}", directory.display(), e))?;
+        entries.sort();
+        let mut files = vec![];
+        for path in entries {
+            if path.is_dir() {
+                if self.recursive {
+                    files.extend(self.files(&path)?);
+                }
+            } else if self.accepts(&path) {
+                files.push(path);
+            }
+        }
+        Ok(files)
+    }
+}
+
+impl Mino {
+    /// Minos of every file in a directory, read in order of their paths so that the
+    /// result is the same on every machine. JSON files are read as by
+    /// [`Mino::supplies_from_json_path`], the others as minos text files.
+    /// Errors name the file, and the line where the text format allows it.
+    ///
+    /// ```
+    /// use tiling_mino_solver::Mino;
+    /// use tiling_mino_solver::directory::DirectoryOptions;
+    /// let options = DirectoryOptions {
+    ///     include: vec!["txt".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// let supplies = Mino::supplies_from_directory("data/minos", &options).unwrap();
+    /// let names: String = supplies.iter().map(|(mino, _)| mino.name).collect();
+    /// assert_eq!(names, "bfcegad");
+    /// ```
+    pub fn supplies_from_directory<P>(
+        directory: P,
+        options: &DirectoryOptions,
+    ) -> Result<Vec<(Self, Supply)>, String>
+    where
+        P: AsRef<Path>,
+    {
+        let mut supplies = vec![];
+        for path in options.files(directory.as_ref())? {
+            let buf =
+                fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let read = if is_json(&path) {
+                read_json_supplies(&buf)
+            } else {
+                Self::read_supplies(buf.lines().map(|s| s.to_string()).collect())
+            };
+            supplies.extend(read.map_err(|e| format!("{}: {}", path.display(), e))?);
+        }
+        symmetry::warn_duplicate_shapes(&supplies);
+        Ok(supplies)
+    }
+}
+
+#[test]
+fn test_supplies_from_directory() {
+    let dir = std::env::temp_dir().join(format!("directory-{}", std::process::id()));
+    fs::create_dir_all(dir.join("more")).unwrap();
+    fs::write(dir.join("b.txt"), "1\nbb").unwrap();
+    fs::write(dir.join("a.txt"), "1\naa\n---\n2\nc").unwrap();
+    fs::write(dir.join("notes.md"), "not minos").unwrap();
+    fs::write(
+        dir.join("more").join("d.json"),
+        r#"[{"name": "d", "shape": [[true]]}]"#,
+    )
+    .unwrap();
+    let names = |options: &DirectoryOptions| -> String {
+        Mino::supplies_from_directory(&dir, options)
+            .unwrap()
+            .iter()
+            .map(|(mino, _)| mino.name)
+            .collect()
+    };
+    let mut options = DirectoryOptions {
+        exclude: vec![".md".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(names(&options), "acb");
+    options.recursive = true;
+    assert_eq!(names(&options), "acbd");
+    options.include = vec!["JSON".to_string()];
+    assert_eq!(names(&options), "d");
+    let error = Mino::supplies_from_directory(&dir, &DirectoryOptions::default()).unwrap_err();
+    assert_eq!(
+        error,
+        format!(
+            "{}: Block at line 1: Invalid mino count 'not minos': invalid digit found in string",
+            dir.join("notes.md").display()
+        )
+    );
+    fs::write(dir.join("a.txt"), "1\naa\n---\n2\nce").unwrap();
+    let error = Mino::supplies_from_path(dir.join("a.txt")).unwrap_err();
+    assert!(error.ends_with(
+        "a.txt: Block at line 4: A mino needs exactly one name character, found ['c', 'e']"
+    ));
+    fs::remove_dir_all(dir).unwrap();
+}
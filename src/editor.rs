@@ -0,0 +1,570 @@
+use crate::colors::{Colors, split_colors};
+use crate::cover::Supply;
+use crate::gui::Scale;
+use crate::json::is_json;
+use crate::{Board, Mino, Shape};
+use nu_ansi_term::{Color, Style};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// What an [`Editor`] edits.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Kind {
+    /// A board: set cells are walls, labels are minos already on the board.
+    Board,
+    /// A mino: set cells are its cells. The template keeps the name and constraints.
+    Mino(Mino),
+}
+
+/// An edit in [`Editor::apply`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Edit {
+    /// Move the cursor by `(dx, dy)`, staying on the grid.
+    Move(isize, isize),
+    /// Toggle the cell at the cursor.
+    Toggle,
+    /// Label the cell at the cursor of a board with a mino name,
+    /// or rename the mino being edited.
+    Paint(char),
+    /// Remove the label at the cursor.
+    Erase,
+    /// Add a column on the right.
+    Wider,
+    /// Remove the rightmost column.
+    Narrower,
+    /// Add a row at the bottom.
+    Taller,
+    /// Remove the bottom row.
+    Shorter,
+    /// Insert a row at the cursor, moving the cursor row down.
+    InsertRow,
+    /// Remove the row at the cursor.
+    RemoveRow,
+    /// Insert a column at the cursor, moving the cursor column right.
+    InsertColumn,
+    /// Remove the column at the cursor.
+    RemoveColumn,
+    /// Add a row or column on each side.
+    Pad,
+    /// Drop blank rows and columns at the edges.
+    Trim,
+}
+
+/// A grid being edited in the terminal, resized through [`Scale`].
+#[derive(Clone, Debug)]
+pub struct Editor {
+    kind: Kind,
+    shape: Shape,
+    labels: HashMap<(usize, usize), char>,
+    /// Target colours of a board, resized with the grid.
+    colors: Option<Colors>,
+    /// Every block of the minos file of a mino, with the edited one at `block`,
+    /// so that saving keeps the others.
+    blocks: Vec<(Mino, Supply)>,
+    block: usize,
+    cursor: (usize, usize),
+    preview: Vec<String>,
+    message: String,
+}
+
+impl Editor {
+    /// An empty board or mino of `width` x `height` cells.
+    pub fn new(kind: Kind, width: usize, height: usize) -> Self {
+        let blocks = match &kind {
+            Kind::Mino(mino) => vec![(mino.clone(), Supply::exactly(1))],
+            Kind::Board => vec![],
+        };
+        Self {
+            kind,
+            shape: Shape::new(vec![vec![false; width]; height]),
+            labels: HashMap::new(),
+            colors: None,
+            blocks,
+            block: 0,
+            cursor: (0, 0),
+            preview: vec![],
+            message: String::new(),
+        }
+    }
+    /// Edit board text: '#' walls, '.' vacant cells and letters for minos on the board,
+    /// optionally followed by target colours.
+    pub fn from_board_str(s: &str) -> Result<Self, String> {
+        let (grid, colors) = split_colors(s);
+        let rows: Vec<Vec<char>> = grid
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect())
+            .collect();
+        let mut editor = Self::new(Kind::Board, 0, 0);
+        editor.shape = Shape::from_str(&grid.replace(|c: char| c != '#' && c != '\n', "."))?;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &c) in row.iter().enumerate() {
+                if c != '.' && c != '#' {
+                    editor.labels.insert((x, y), c);
+                }
+            }
+        }
+        editor.colors = colors.as_deref().map(Colors::from_str).transpose()?;
+        Ok(editor)
+    }
+    /// Edit `mino`, keeping its name and constraints.
+    pub fn from_mino(mino: Mino) -> Self {
+        let mut editor = Self::new(Kind::Mino(mino.clone()), 0, 0);
+        editor.shape = mino.shape;
+        editor
+    }
+    /// Edit the mino of the `index`-th of `blocks`, which are written back with it
+    /// by [`Editor::save`].
+    pub fn from_supplies(blocks: Vec<(Mino, Supply)>, index: usize) -> Result<Self, String> {
+        let (mino, _) = blocks.get(index).ok_or("No such mino in the file")?;
+        let mut editor = Self::from_mino(mino.clone());
+        editor.blocks = blocks;
+        editor.block = index;
+        Ok(editor)
+    }
+    /// Open a board or the first mino of a minos file at `path`,
+    /// or start a 5x5 one when there is no file yet.
+    pub fn open<P>(path: P, board: bool) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        if !board {
+            return Self::open_mino(path, None);
+        }
+        if !path.as_ref().exists() {
+            Ok(Self::new(Kind::Board, 5, 5))
+        } else if is_json(&path) {
+            Self::from_board_str(&Board::from_json_path(path)?.to_lines().join("\n"))
+        } else {
+            Self::from_board_str(&fs::read_to_string(path).map_err(|e| e.to_string())?)
+        }
+    }
+    /// Open the mino named `name`, or the first one, of the minos file at `path`,
+    /// or start a 5x5 one when there is no file yet.
+    pub fn open_mino<P>(path: P, name: Option<char>) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        if !path.as_ref().exists() {
+            let mino = Mino::new(name.unwrap_or('a'), Shape::new(vec![]));
+            return Ok(Self::new(Kind::Mino(mino), 5, 5));
+        }
+        let blocks = Mino::supplies_from_path(&path)?;
+        let index = match name {
+            Some(name) => blocks
+                .iter()
+                .position(|(mino, _)| mino.name == name)
+                .ok_or(format!("No mino '{}' in the file", name))?,
+            None if blocks.is_empty() => return Err("No mino in the file".to_string()),
+            None => 0,
+        };
+        Self::from_supplies(blocks, index)
+    }
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+    pub fn apply(&mut self, edit: Edit) {
+        self.message.clear();
+        let (x, y) = self.cursor;
+        let on_grid = x < self.shape.width() && y < self.shape.height();
+        match edit {
+            Edit::Move(dx, dy) => {
+                self.cursor = (
+                    x.saturating_add_signed(dx)
+                        .min(self.shape.width().saturating_sub(1)),
+                    y.saturating_add_signed(dy)
+                        .min(self.shape.height().saturating_sub(1)),
+                );
+            }
+            Edit::Toggle if on_grid => {
+                self.labels.remove(&(x, y));
+                self.shape.toggle(x, y);
+            }
+            Edit::Paint(name) => match &mut self.kind {
+                Kind::Mino(mino) => mino.name = name,
+                Kind::Board if on_grid => {
+                    if self.shape.is_wall(x, y) {
+                        self.shape.toggle(x, y);
+                    }
+                    self.labels.insert((x, y), name);
+                }
+                Kind::Board => {}
+            },
+            Edit::Erase => {
+                self.labels.remove(&(x, y));
+            }
+            Edit::Wider => self.increment_width(),
+            Edit::Taller => self.increment_height(),
+            Edit::Narrower => self.decrement_width(),
+            Edit::Shorter => self.decrement_height(),
+            Edit::InsertRow => self.insert_row(y.min(self.shape.height())),
+            Edit::InsertColumn => self.insert_column(x.min(self.shape.width())),
+            Edit::RemoveRow if on_grid => self.remove_row(y),
+            Edit::RemoveColumn if on_grid => self.remove_column(x),
+            Edit::Pad => {
+                self.pad(1, 1, 1, 1);
+                self.cursor = (x + 1, y + 1);
+            }
+            Edit::Trim => {
+                let (dx, dy) = self.trim_with_offset();
+                self.cursor = (x.saturating_sub(dx), y.saturating_sub(dy));
+            }
+            Edit::Toggle | Edit::RemoveRow | Edit::RemoveColumn => {}
+        }
+        if !matches!(
+            edit,
+            Edit::Move(..) | Edit::Toggle | Edit::Paint(_) | Edit::Erase
+        ) {
+            self.apply(Edit::Move(0, 0));
+        }
+    }
+    /// The grid as text, with labels of a board in place of its cells.
+    fn grid_lines(&self) -> Vec<String> {
+        let name = match &self.kind {
+            Kind::Mino(mino) => mino.name,
+            Kind::Board => '#',
+        };
+        (0..self.shape.height())
+            .map(|y| {
+                (0..self.shape.width())
+                    .map(|x| match self.labels.get(&(x, y)) {
+                        Some(&label) => label,
+                        None if self.shape.is_wall(x, y) => name,
+                        None => '.',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+    /// The grid followed by the target colours of a board, if kept.
+    fn text_lines(&self) -> Vec<String> {
+        let mut lines = self.grid_lines();
+        if let Some(colors) = &self.colors {
+            lines.push("=".to_string());
+            lines.extend(colors.to_lines());
+        }
+        lines
+    }
+    /// The board being edited, with its labels put on as minos from `minos`.
+    pub fn board(&self, minos: &[Mino]) -> Result<Board, String> {
+        Board::from_str_with_minos(&self.text_lines().join("\n"), minos)
+    }
+    /// The mino being edited, trimmed. Fails for minos which are not connected.
+    pub fn mino(&self) -> Result<Mino, String> {
+        let Kind::Mino(template) = &self.kind else {
+            return Err("A board is being edited".to_string());
+        };
+        let mut shape = self.shape.clone();
+        shape.trim();
+        let mut mino = Mino::from_str(&Mino::new(template.name, shape).to_lines().join("\n"))?;
+        mino.constraints = template.constraints.clone();
+        mino.label = template.label.clone();
+        mino.display_color = template.display_color.clone();
+        if mino.shape == template.shape {
+            mino.colors = template.colors.clone();
+            mino.back_colors = template.back_colors.clone();
+        }
+        Ok(mino)
+    }
+    /// Write the board or mino to `path`, as JSON if its extension is `json`
+    /// and in the text format otherwise. Labels of a board have to be minos from `minos`
+    /// to be written as JSON. A mino replaces its block among the others of its file.
+    pub fn save<P>(&self, path: P, minos: &[Mino]) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        match &self.kind {
+            Kind::Board if is_json(&path) => self.board(minos)?.save_to_path(path),
+            Kind::Board => {
+                fs::write(path, self.text_lines().join("\n") + "\n").map_err(|e| e.to_string())
+            }
+            Kind::Mino(_) => {
+                let mut blocks = self.blocks.clone();
+                blocks[self.block].0 = self.mino()?;
+                Mino::save_supplies_to_path(path, &blocks)
+            }
+        }
+    }
+    /// Solve the board being edited with `minos` and keep the solution to show,
+    /// or the reason why there is none, as the message. The search does not print
+    /// its progress, which would write over the screen.
+    pub fn solve_preview(&mut self, minos: &[Mino]) {
+        let nodes = AtomicUsize::new(0);
+        let solved = self.board(minos).and_then(|board| {
+            let unplaced = board.unplaced_minos(minos)?;
+            if let Some(reason) = board.infeasibility(&unplaced) {
+                return Err(reason);
+            }
+            board
+                .tile_counting(&unplaced, &nodes)
+                .ok_or("No tiling found by the search".to_string())
+        });
+        let nodes = nodes.load(Ordering::Relaxed);
+        match solved {
+            Ok(board) => {
+                self.preview = board
+                    .pretty_shape()
+                    .lines()
+                    .map(|s| s.to_string())
+                    .collect();
+                self.message = format!("Solved after {} nodes", nodes);
+            }
+            Err(reason) if nodes > 0 => {
+                self.preview.clear();
+                self.message = format!("{} after {} nodes", reason, nodes);
+            }
+            Err(reason) => {
+                self.preview.clear();
+                self.message = reason;
+            }
+        }
+    }
+    /// The screen: the grid with the cursor, the last solver preview and the message.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .grid_lines()
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        let style = if (x, y) == self.cursor {
+                            Style::new().reverse()
+                        } else if c == '#' {
+                            Color::Black.on(Color::White)
+                        } else if c == '.' {
+                            Style::new()
+                        } else {
+                            Color::Cyan.normal()
+                        };
+                        style.paint(c.to_string()).to_string()
+                    })
+                    .collect()
+            })
+            .collect();
+        lines.push(format!("{}x{}", self.shape.width(), self.shape.height()));
+        if !self.preview.is_empty() {
+            lines.push(String::new());
+            lines.extend(self.preview.iter().cloned());
+        }
+        lines.push(self.message.clone());
+        lines
+    }
+}
+
+/// New cells are blank, walls of a board or empty cells of a mino, as for [`Board`]
+/// and [`Mino`], so that trimming undoes padding. Labels and target colours move
+/// with their cells.
+impl Scale for Editor {
+    fn width(&self) -> usize {
+        self.shape.width()
+    }
+    fn height(&self) -> usize {
+        self.shape.height()
+    }
+    fn insert_row(&mut self, y0: usize) {
+        self.shape.insert_row(y0);
+        if self.kind == Kind::Board {
+            (0..self.shape.width()).for_each(|x| self.shape.put_on(x, y0, true));
+        }
+        if let Some(colors) = &mut self.colors {
+            colors.insert_row(y0);
+        }
+        self.move_labels(|x, y| Some((x, if y >= y0 { y + 1 } else { y })));
+    }
+    fn remove_row(&mut self, y0: usize) {
+        self.shape.remove_row(y0);
+        if let Some(colors) = &mut self.colors {
+            colors.remove_row(y0);
+        }
+        self.move_labels(|x, y| (y != y0).then_some((x, if y > y0 { y - 1 } else { y })));
+    }
+    fn insert_column(&mut self, x0: usize) {
+        self.shape.insert_column(x0);
+        if self.kind == Kind::Board {
+            (0..self.shape.height()).for_each(|y| self.shape.put_on(x0, y, true));
+        }
+        if let Some(colors) = &mut self.colors {
+            colors.insert_column(x0);
+        }
+        self.move_labels(|x, y| Some((if x >= x0 { x + 1 } else { x }, y)));
+    }
+    fn remove_column(&mut self, x0: usize) {
+        self.shape.remove_column(x0);
+        if let Some(colors) = &mut self.colors {
+            colors.remove_column(x0);
+        }
+        self.move_labels(|x, y| (x != x0).then_some((if x > x0 { x - 1 } else { x }, y)));
+    }
+    fn is_blank_row(&self, y: usize) -> bool {
+        (0..self.shape.width()).all(|x| self.is_blank(x, y))
+    }
+    fn is_blank_column(&self, x: usize) -> bool {
+        (0..self.shape.height()).all(|y| self.is_blank(x, y))
+    }
+}
+
+impl Editor {
+    /// Move the label at `(x, y)` to `moved(x, y)`, dropping it when that is `None`.
+    fn move_labels(&mut self, moved: impl Fn(usize, usize) -> Option<(usize, usize)>) {
+        self.labels = self
+            .labels
+            .drain()
+            .filter_map(|((x, y), label)| moved(x, y).map(|cell| (cell, label)))
+            .collect();
+    }
+    fn is_blank(&self, x: usize, y: usize) -> bool {
+        self.shape.is_wall(x, y) == (self.kind == Kind::Board) && !self.labels.contains_key(&(x, y))
+    }
+}
+
+#[test]
+fn test_edit_board() {
+    let mut editor = Editor::from_board_str("#..\n.aa\n..a").unwrap();
+    editor.apply(Edit::Toggle);
+    editor.apply(Edit::Wider);
+    editor.apply(Edit::Move(3, 0));
+    editor.apply(Edit::Toggle);
+    editor.apply(Edit::Move(-3, 2));
+    editor.apply(Edit::Paint('b'));
+    assert_eq!(editor.grid_lines(), vec!["....", ".aa#", "b.a#"]);
+    editor.apply(Edit::Shorter);
+    editor.apply(Edit::Erase);
+    assert_eq!(editor.grid_lines(), vec!["....", ".aa#"]);
+    assert_eq!(editor.cursor, (0, 1));
+    let minos = vec![
+        Mino::from_str("aa").unwrap(),
+        Mino::from_str("b\nb").unwrap(),
+    ];
+    let board = editor.board(&minos).unwrap();
+    assert_eq!(board.fixed_mino_transforms().len(), 1);
+    editor.solve_preview(&minos[..1]);
+    assert_eq!(
+        editor.message(),
+        "Area: the minos cover 0 cells but the board has 5 vacant cells"
+    );
+    editor.solve_preview(&[minos[0].clone(), minos[1].clone(), minos[1].clone()]);
+    assert_eq!(
+        editor.message(),
+        "Area: the minos cover 4 cells but the board has 5 vacant cells"
+    );
+}
+
+#[test]
+fn test_solve_preview_many_minos() {
+    let mut editor = Editor::from_board_str(".........").unwrap();
+    let minos = vec![Mino::from_str("a").unwrap(); 9];
+    editor.solve_preview(&minos);
+    assert!(editor.message().starts_with("Solved after"));
+    assert_eq!(editor.preview, vec!["aaaaaaaaa"]);
+}
+
+#[test]
+fn test_edit_mino() {
+    let mut editor = Editor::from_mino(Mino::from_str("aa\n=\nBW").unwrap());
+    editor.apply(Edit::Taller);
+    editor.apply(Edit::Move(0, 1));
+    editor.apply(Edit::Toggle);
+    editor.apply(Edit::Paint('z'));
+    let mino = editor.mino().unwrap();
+    assert_eq!(mino.to_lines(), vec!["zz", "z."]);
+    assert_eq!(mino.colors, None);
+    editor.apply(Edit::Toggle);
+    editor.apply(Edit::Move(1, 0));
+    editor.apply(Edit::Toggle);
+    editor.apply(Edit::Move(0, -1));
+    editor.apply(Edit::Toggle);
+    assert_eq!(editor.mino().unwrap_err(), "Mino 'z' is not connected");
+}
+
+#[test]
+fn test_editor_save() {
+    let dir = std::env::temp_dir().join(format!("editor-save-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let mut editor = Editor::new(Kind::Board, 2, 1);
+    editor.apply(Edit::Paint('a'));
+    let minos = vec![Mino::from_str("a").unwrap()];
+    editor.save(dir.join("board.txt"), &minos).unwrap();
+    assert_eq!(fs::read_to_string(dir.join("board.txt")).unwrap(), "a.\n");
+    editor.save(dir.join("board.json"), &minos).unwrap();
+    let board: Board =
+        serde_json::from_str(&fs::read_to_string(dir.join("board.json")).unwrap()).unwrap();
+    assert_eq!(board, editor.board(&minos).unwrap());
+    assert!(editor.save(dir.join("board.json"), &[]).is_err());
+    let editor = Editor::from_mino(Mino::from_str("b.\nbb").unwrap());
+    editor.save(dir.join("minos.txt"), &[]).unwrap();
+    assert_eq!(
+        Mino::minos_from_path(dir.join("minos.txt")).unwrap(),
+        vec![editor.mino().unwrap()]
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_edit_board_resize_at_cursor() {
+    let original = Editor::from_board_str("#..\n.aa\n=\nBW.\nW.B").unwrap();
+    let mut editor = original.clone();
+    editor.apply(Edit::Move(1, 1));
+    editor.apply(Edit::InsertColumn);
+    editor.apply(Edit::InsertRow);
+    assert_eq!(editor.grid_lines(), vec!["##..", "####", ".#aa"]);
+    assert_eq!(
+        editor.colors.as_ref().unwrap().to_lines(),
+        vec!["B.W.", "....", "W..B"]
+    );
+    editor.apply(Edit::RemoveRow);
+    editor.apply(Edit::RemoveColumn);
+    assert_eq!(editor.text_lines(), original.text_lines());
+    editor.apply(Edit::Pad);
+    assert_eq!(
+        editor.grid_lines(),
+        vec!["#####", "##..#", "#.aa#", "#####"]
+    );
+    assert_eq!(editor.cursor, (2, 2));
+    editor.apply(Edit::Trim);
+    assert_eq!(editor.text_lines(), original.text_lines());
+    assert_eq!(editor.cursor, (1, 1));
+}
+
+#[test]
+fn test_editor_save_keeps_other_minos() {
+    let dir = std::env::temp_dir().join(format!("editor-blocks-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("minos.txt");
+    fs::write(&path, "*\naa\n-----\n2\nb\nb\n-----\n1..3\nc.\ncc\n").unwrap();
+    let mut editor = Editor::open_mino(&path, Some('b')).unwrap();
+    editor.apply(Edit::Wider);
+    editor.apply(Edit::Move(1, 1));
+    editor.apply(Edit::Toggle);
+    editor.save(&path, &[]).unwrap();
+    let supplies = Mino::supplies_from_path(&path).unwrap();
+    let lines: Vec<(Vec<String>, Supply)> = supplies
+        .iter()
+        .map(|(mino, supply)| (mino.to_lines(), *supply))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (vec!["aa".to_string()], Supply::unlimited()),
+            (vec!["b.".to_string(), "bb".to_string()], Supply::exactly(2)),
+            (
+                vec!["c.".to_string(), "cc".to_string()],
+                Supply {
+                    min: 1,
+                    max: Some(3)
+                }
+            ),
+        ]
+    );
+    assert!(Editor::open_mino(&path, Some('d')).is_err());
+    assert!(editor.save(dir.join("minos.json"), &[]).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,266 @@
+use crate::Shape;
+
+/// Which neighbouring cells count as connected.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Connectivity {
+    /// Cells sharing an edge.
+    Four,
+    /// Cells sharing an edge or a corner.
+    Eight,
+}
+
+impl Connectivity {
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// Position and size of the smallest rectangle around the cells of a shape.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BoundingBox {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Shape {
+    /// The shape with its cells and gaps swapped, e.g. the vacant cells of a board.
+    pub fn inverted(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .map(|row| row.iter().map(|&b| !b).collect())
+                .collect(),
+        )
+    }
+    /// Groups of connected `true` cells as `(x, y)`, in reading order of their first cell.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Shape, geometry::Connectivity};
+    /// let shape = Shape::from_str("#.#\n.#.").unwrap();
+    /// assert_eq!(shape.components(Connectivity::Four).len(), 3);
+    /// assert_eq!(shape.components(Connectivity::Eight).len(), 1);
+    /// ```
+    pub fn components(&self, connectivity: Connectivity) -> Vec<Vec<(usize, usize)>> {
+        self.regions(true, connectivity)
+    }
+    /// Groups of connected cells equal to `value`.
+    fn regions(&self, value: bool, connectivity: Connectivity) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = vec![vec![false; self.width()]; self.height()];
+        let mut regions = vec![];
+        for (x, y, b) in self.coordinates() {
+            if b != value || seen[y][x] {
+                continue;
+            }
+            seen[y][x] = true;
+            let mut region = vec![];
+            let mut stack = vec![(x, y)];
+            while let Some((x, y)) = stack.pop() {
+                region.push((x, y));
+                for &(dx, dy) in connectivity.offsets() {
+                    let (Some(nx), Some(ny)) = (x.checked_add_signed(dx), y.checked_add_signed(dy))
+                    else {
+                        continue;
+                    };
+                    if nx < self.width()
+                        && ny < self.height()
+                        && self.0[ny][nx] == value
+                        && !seen[ny][nx]
+                    {
+                        seen[ny][nx] = true;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            region.sort_by_key(|&(x, y)| (y, x));
+            regions.push(region);
+        }
+        regions
+    }
+    /// Number of 4-connected groups of gaps which are enclosed by cells,
+    /// i.e. which do not reach the edge of the grid.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::Shape;
+    /// assert_eq!(Shape::from_str("###\n#.#\n###").unwrap().holes(), 1);
+    /// assert_eq!(Shape::from_str("###\n#..\n###").unwrap().holes(), 0);
+    /// ```
+    pub fn holes(&self) -> usize {
+        self.regions(false, Connectivity::Four)
+            .iter()
+            .filter(|region| {
+                !region.iter().any(|&(x, y)| {
+                    x == 0 || y == 0 || x + 1 == self.width() || y + 1 == self.height()
+                })
+            })
+            .count()
+    }
+    /// Number of cell edges between a cell and a gap or the outside.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::Shape;
+    /// assert_eq!(Shape::from_str("##\n##").unwrap().perimeter(), 8);
+    /// assert_eq!(Shape::from_str("#.\n##").unwrap().perimeter(), 8);
+    /// ```
+    pub fn perimeter(&self) -> usize {
+        let filled = |x: Option<usize>, y: Option<usize>| match (x, y) {
+            (Some(x), Some(y)) => x < self.width() && y < self.height() && self.is_wall(x, y),
+            _ => false,
+        };
+        self.coordinates()
+            .into_iter()
+            .filter(|&(_, _, b)| b)
+            .map(|(x, y, _)| {
+                Connectivity::Four
+                    .offsets()
+                    .iter()
+                    .filter(|&&(dx, dy)| {
+                        !filled(x.checked_add_signed(dx), y.checked_add_signed(dy))
+                    })
+                    .count()
+            })
+            .sum()
+    }
+    /// The smallest rectangle containing every cell, or `None` when there are no cells.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        let cells: Vec<(usize, usize)> = self
+            .coordinates()
+            .into_iter()
+            .filter(|&(_, _, b)| b)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        let x = cells.iter().map(|&(x, _)| x).min()?;
+        let y = cells.iter().map(|&(_, y)| y).min()?;
+        let right = cells.iter().map(|&(x, _)| x).max()?;
+        let bottom = cells.iter().map(|&(_, y)| y).max()?;
+        Some(BoundingBox {
+            x,
+            y,
+            width: right - x + 1,
+            height: bottom - y + 1,
+        })
+    }
+    /// Numbers of cells on the dark and light squares of a checkerboard
+    /// whose top left square is dark.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::Shape;
+    /// assert_eq!(Shape::from_str(".#.\n###").unwrap().checkerboard_balance(), (1, 3));
+    /// ```
+    pub fn checkerboard_balance(&self) -> (usize, usize) {
+        self.coordinates().into_iter().filter(|&(_, _, b)| b).fold(
+            (0, 0),
+            |(dark, light), (x, y, _)| {
+                if (x + y) % 2 == 0 {
+                    (dark + 1, light)
+                } else {
+                    (dark, light + 1)
+                }
+            },
+        )
+    }
+    /// Number of cells in each row, from the top.
+    pub fn row_profile(&self) -> Vec<usize> {
+        self.0
+            .iter()
+            .map(|row| row.iter().filter(|&&b| b).count())
+            .collect()
+    }
+    /// Number of cells in each column, from the left.
+    pub fn column_profile(&self) -> Vec<usize> {
+        (0..self.width())
+            .map(|x| (0..self.height()).filter(|&y| self.is_wall(x, y)).count())
+            .collect()
+    }
+    /// A summary of the measures above, one per line.
+    pub fn analysis_lines(&self) -> Vec<String> {
+        let join = |profile: Vec<usize>| {
+            profile
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        };
+        let (dark, light) = self.checkerboard_balance();
+        let bounding_box = match self.bounding_box() {
+            Some(b) => format!("{}x{} at ({}, {})", b.width, b.height, b.x, b.y),
+            None => "none".to_string(),
+        };
+        vec![
+            format!("cells: {}", self.count_wall()),
+            format!(
+                "components: {} (4-connected), {} (8-connected)",
+                self.components(Connectivity::Four).len(),
+                self.components(Connectivity::Eight).len()
+            ),
+            format!("holes: {}", self.holes()),
+            format!("perimeter: {}", self.perimeter()),
+            format!("bounding box: {}", bounding_box),
+            format!("checkerboard: {} dark, {} light", dark, light),
+            format!("rows: {}", join(self.row_profile())),
+            format!("columns: {}", join(self.column_profile())),
+        ]
+    }
+}
+
+#[test]
+fn test_shape_geometry() {
+    use std::str::FromStr;
+    let shape = Shape::from_str("....\n.##.\n.#..\n.###").unwrap();
+    assert_eq!(shape.components(Connectivity::Four).len(), 1);
+    assert_eq!(shape.holes(), 0);
+    assert_eq!(shape.perimeter(), 14);
+    assert_eq!(
+        shape.bounding_box(),
+        Some(BoundingBox {
+            x: 1,
+            y: 1,
+            width: 3,
+            height: 3
+        })
+    );
+    assert_eq!(shape.checkerboard_balance(), (3, 3));
+    assert_eq!(shape.row_profile(), vec![0, 2, 1, 3]);
+    assert_eq!(shape.column_profile(), vec![0, 3, 2, 1]);
+    assert_eq!(Shape::from_str("..").unwrap().bounding_box(), None);
+}
+
+#[test]
+fn test_board_geometry() {
+    use crate::Board;
+    use std::str::FromStr;
+    let board = Board::from_str("..#\n.#.\n..#").unwrap();
+    let vacant = board.shape.inverted();
+    assert_eq!(vacant.count_wall(), 6);
+    assert_eq!(vacant.components(Connectivity::Four).len(), 2);
+    assert_eq!(vacant.components(Connectivity::Eight).len(), 1);
+    assert_eq!(vacant.checkerboard_balance(), (2, 4));
+}
+
+#[test]
+fn test_mino_from_str_when_disconnected() {
+    use crate::Mino;
+    use std::str::FromStr;
+    assert_eq!(
+        Mino::from_str("a.\n.a").unwrap_err(),
+        "Mino 'a' is not connected"
+    );
+    assert!(Mino::from_str("a.\naa").is_ok());
+}
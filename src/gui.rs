@@ -1,17 +1,110 @@
-use crate::Shape;
+use crate::colors::Colors;
+use crate::{Board, Mino, Shape, TransForm};
 
+/// A grid which can be resized, with new cells blank.
+///
+/// Implementors provide inserting and removing single rows and columns;
+/// growing, padding, shifting, cropping and trimming are built on those.
 pub trait Scale
 where
     Self: Clone + Sized,
 {
     fn width(&self) -> usize;
     fn height(&self) -> usize;
-    fn increment_width(&mut self);
-    fn decrement_width(&mut self);
-    fn increment_height(&mut self);
-    fn decrement_height(&mut self);
+    /// Insert a blank row before row `y`, or at the bottom when `y` is the height.
+    fn insert_row(&mut self, y: usize);
+    /// Remove row `y`. Panics if `y` is not less than the height.
+    fn remove_row(&mut self, y: usize);
+    /// Insert a blank column before column `x`, or at the right when `x` is the width.
+    fn insert_column(&mut self, x: usize);
+    /// Remove column `x`. Panics if `x` is not less than the width.
+    fn remove_column(&mut self, x: usize);
+    /// Whether row `y` may be dropped by [`Scale::trim_with_offset`].
+    fn is_blank_row(&self, y: usize) -> bool;
+    /// Whether column `x` may be dropped by [`Scale::trim_with_offset`].
+    fn is_blank_column(&self, x: usize) -> bool;
+
+    fn increment_width(&mut self) {
+        self.insert_column(self.width());
+    }
+    fn decrement_width(&mut self) {
+        if self.width() > 0 {
+            self.remove_column(self.width() - 1);
+        }
+    }
+    fn increment_height(&mut self) {
+        self.insert_row(self.height());
+    }
+    fn decrement_height(&mut self) {
+        if self.height() > 0 {
+            self.remove_row(self.height() - 1);
+        }
+    }
+    /// Add blank rows and columns on each side.
+    fn pad(&mut self, top: usize, right: usize, bottom: usize, left: usize) {
+        (0..top).for_each(|_| self.insert_row(0));
+        (0..bottom).for_each(|_| self.insert_row(self.height()));
+        (0..left).for_each(|_| self.insert_column(0));
+        (0..right).for_each(|_| self.insert_column(self.width()));
+    }
+    /// Move the content by `(dx, dy)` keeping the size: cells moved over an edge
+    /// are dropped and blank cells come in from the other side.
+    fn shift(&mut self, dx: isize, dy: isize) {
+        let (width, height) = (self.width(), self.height());
+        for _ in 0..dx.unsigned_abs().min(width) {
+            if dx > 0 {
+                self.remove_column(width - 1);
+                self.insert_column(0);
+            } else {
+                self.remove_column(0);
+                self.insert_column(width - 1);
+            }
+        }
+        for _ in 0..dy.unsigned_abs().min(height) {
+            if dy > 0 {
+                self.remove_row(height - 1);
+                self.insert_row(0);
+            } else {
+                self.remove_row(0);
+                self.insert_row(height - 1);
+            }
+        }
+    }
+    /// Keep only the `width` x `height` rectangle with its top left at `(x, y)`,
+    /// clipped to the grid.
+    fn crop(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        while self.width() > x.saturating_add(width) {
+            self.remove_column(self.width() - 1);
+        }
+        (0..x.min(self.width())).for_each(|_| self.remove_column(0));
+        while self.height() > y.saturating_add(height) {
+            self.remove_row(self.height() - 1);
+        }
+        (0..y.min(self.height())).for_each(|_| self.remove_row(0));
+    }
+    /// Drop blank rows and columns at the edges. Returns where the top left of
+    /// what is left was before, as `(x, y)`.
+    fn trim_with_offset(&mut self) -> (usize, usize) {
+        let (mut x, mut y) = (0, 0);
+        while self.height() > 0 && self.is_blank_row(0) {
+            self.remove_row(0);
+            y += 1;
+        }
+        while self.height() > 0 && self.is_blank_row(self.height() - 1) {
+            self.remove_row(self.height() - 1);
+        }
+        while self.width() > 0 && self.is_blank_column(0) {
+            self.remove_column(0);
+            x += 1;
+        }
+        while self.width() > 0 && self.is_blank_column(self.width() - 1) {
+            self.remove_column(self.width() - 1);
+        }
+        (x, y)
+    }
 }
 
+/// New cells are vacant; blank rows and columns have no set cell.
 impl Scale for Shape {
     fn width(&self) -> usize {
         self.width()
@@ -19,22 +112,180 @@ impl Scale for Shape {
     fn height(&self) -> usize {
         self.height()
     }
-    fn increment_width(&mut self) {
-        self.0.iter_mut().for_each(|row| {
-            row.push(bool::default());
-        });
+    fn insert_row(&mut self, y: usize) {
+        let width = self.width();
+        self.0.insert(y, vec![bool::default(); width]);
     }
-    fn decrement_width(&mut self) {
+    fn remove_row(&mut self, y: usize) {
+        self.0.remove(y);
+    }
+    fn insert_column(&mut self, x: usize) {
+        self.0
+            .iter_mut()
+            .for_each(|row| row.insert(x, bool::default()));
+    }
+    fn remove_column(&mut self, x: usize) {
         self.0.iter_mut().for_each(|row| {
-            row.pop();
+            row.remove(x);
         });
     }
-    fn increment_height(&mut self) {
-        let width = self.width();
-        self.0.push(vec![bool::default(); width]);
+    fn is_blank_row(&self, y: usize) -> bool {
+        self.0[y].iter().all(|&b| !b)
     }
-    fn decrement_height(&mut self) {
-        self.0.pop();
+    fn is_blank_column(&self, x: usize) -> bool {
+        self.0.iter().all(|row| !row[x])
+    }
+}
+
+/// Colours are kept aligned with the shape; blank means no cell of the shape.
+impl Scale for Mino {
+    fn width(&self) -> usize {
+        self.shape.width()
+    }
+    fn height(&self) -> usize {
+        self.shape.height()
+    }
+    fn insert_row(&mut self, y: usize) {
+        self.shape.insert_row(y);
+        self.colors_mut().for_each(|colors| colors.insert_row(y));
+    }
+    fn remove_row(&mut self, y: usize) {
+        self.shape.remove_row(y);
+        self.colors_mut().for_each(|colors| colors.remove_row(y));
+    }
+    fn insert_column(&mut self, x: usize) {
+        self.shape.insert_column(x);
+        self.colors_mut().for_each(|colors| colors.insert_column(x));
+    }
+    fn remove_column(&mut self, x: usize) {
+        self.shape.remove_column(x);
+        self.colors_mut().for_each(|colors| colors.remove_column(x));
+    }
+    fn is_blank_row(&self, y: usize) -> bool {
+        self.shape.is_blank_row(y)
+    }
+    fn is_blank_column(&self, x: usize) -> bool {
+        self.shape.is_blank_column(x)
+    }
+}
+
+impl Mino {
+    fn colors_mut(&mut self) -> impl Iterator<Item = &mut Colors> {
+        self.colors.iter_mut().chain(self.back_colors.iter_mut())
+    }
+}
+
+/// New cells are walls, and blank rows and columns are walls without minos, as
+/// around an irregular board, so that trimming undoes padding. Target colours are
+/// kept aligned with the shape. Minos on the board move with their cells; a mino
+/// which would be cut apart or pushed over an edge is taken off. The undo history
+/// is cleared.
+impl Scale for Board {
+    fn width(&self) -> usize {
+        self.width()
+    }
+    fn height(&self) -> usize {
+        self.height()
+    }
+    fn insert_row(&mut self, y0: usize) {
+        self.reshape(
+            |shape| {
+                shape.insert_row(y0);
+                (0..shape.width()).for_each(|x| shape.put_on(x, y0, true));
+            },
+            |colors| colors.insert_row(y0),
+            |x, y| Some((x, if y >= y0 { y + 1 } else { y })),
+        );
+    }
+    fn remove_row(&mut self, y0: usize) {
+        self.reshape(
+            |shape| shape.remove_row(y0),
+            |colors| colors.remove_row(y0),
+            |x, y| (y != y0).then_some((x, if y > y0 { y - 1 } else { y })),
+        );
+    }
+    fn insert_column(&mut self, x0: usize) {
+        self.reshape(
+            |shape| {
+                shape.insert_column(x0);
+                (0..shape.height()).for_each(|y| shape.put_on(x0, y, true));
+            },
+            |colors| colors.insert_column(x0),
+            |x, y| Some((if x >= x0 { x + 1 } else { x }, y)),
+        );
+    }
+    fn remove_column(&mut self, x0: usize) {
+        self.reshape(
+            |shape| shape.remove_column(x0),
+            |colors| colors.remove_column(x0),
+            |x, y| (x != x0).then_some((if x > x0 { x - 1 } else { x }, y)),
+        );
+    }
+    fn is_blank_row(&self, y: usize) -> bool {
+        (0..self.width()).all(|x| self.is_wall(x, y) && self.mino_at(x, y).is_none())
+    }
+    fn is_blank_column(&self, x: usize) -> bool {
+        (0..self.height()).all(|y| self.is_wall(x, y) && self.mino_at(x, y).is_none())
+    }
+}
+
+impl Board {
+    /// Change the grid by `edit_shape` and `edit_colors`, where a cell at `(x, y)`
+    /// ends up at `moved(x, y)`, or is gone when it is `None`.
+    fn reshape(
+        &mut self,
+        edit_shape: impl Fn(&mut Shape),
+        edit_colors: impl Fn(&mut Colors),
+        moved: impl Fn(usize, usize) -> Option<(usize, usize)>,
+    ) {
+        let mut placed = vec![];
+        while !self.mino_transforms.is_empty() {
+            let index = self.mino_transforms.len() - 1;
+            let fixed = index < self.fixed;
+            let (mino, transform) = &self.mino_transforms[index];
+            let cells = self
+                .covered_cells(&mino.oriented(&transform.orientation()), transform)
+                .expect("mino must be inside the board");
+            let cells: Option<Vec<(usize, usize)>> =
+                cells.into_iter().map(|(x, y)| moved(x, y)).collect();
+            let (mino, transform) = self.unplace_mino(index);
+            placed.push((mino, transform, fixed, cells));
+        }
+        edit_shape(&mut self.shape);
+        if let Some(colors) = &mut self.target_colors {
+            edit_colors(colors);
+        }
+        for (mino, transform, fixed, cells) in placed.into_iter().rev() {
+            let Some(cells) = cells else {
+                continue;
+            };
+            let oriented = mino.oriented(&transform.orientation());
+            let first = oriented
+                .shape
+                .coordinates()
+                .into_iter()
+                .find(|&(_, _, b)| b)
+                .map(|(x, y, _)| (x, y));
+            let Some((mino_x, mino_y)) = first else {
+                continue;
+            };
+            let (new_x, new_y) = cells[0];
+            let moved_transform = new_x
+                .checked_sub(mino_x)
+                .zip(new_y.checked_sub(mino_y))
+                .map(|(x, y)| TransForm::new(x, y, transform.orientation()));
+            match moved_transform {
+                Some(moved_transform)
+                    if self.covered_cells(&oriented, &moved_transform) == Some(cells.clone())
+                        && cells.iter().all(|&(x, y)| !self.is_wall(x, y)) =>
+                {
+                    self.place_mino(self.mino_transforms.len(), mino, moved_transform, fixed)
+                }
+                _ => {}
+            }
+        }
+        self.undo_stack.clear();
+        self.redo_stack.clear();
     }
 }
 
@@ -94,3 +345,98 @@ fn scale_shape_test() {
     assert_eq!(shape.height(), 2);
     assert_eq!(shape, first);
 }
+
+#[test]
+fn scale_shape_edit_anywhere_test() {
+    use std::str::FromStr;
+    let mut shape = Shape::from_str("#.\n.#").unwrap();
+    shape.insert_row(1);
+    shape.insert_column(1);
+    assert_eq!(shape, Shape::from_str("#..\n...\n..#").unwrap());
+    shape.remove_column(0);
+    shape.remove_row(2);
+    assert_eq!(shape, Shape::from_str("..\n..").unwrap());
+    let mut shape = Shape::from_str("#.\n.#").unwrap();
+    shape.pad(1, 0, 0, 2);
+    assert_eq!(shape, Shape::from_str("....\n..#.\n...#").unwrap());
+    assert_eq!(shape.trim_with_offset(), (2, 1));
+    assert_eq!(shape, Shape::from_str("#.\n.#").unwrap());
+    shape.shift(1, 1);
+    assert_eq!(shape, Shape::from_str("..\n.#").unwrap());
+    shape.shift(-5, 0);
+    assert_eq!(shape, Shape::from_str("..\n..").unwrap());
+    let mut shape = Shape::from_str("#..\n.#.\n..#").unwrap();
+    shape.crop(1, 1, 5, 1);
+    assert_eq!(shape, Shape::from_str("#.").unwrap());
+}
+
+#[test]
+fn scale_board_test() {
+    use crate::Orientation;
+    use std::str::FromStr;
+    let minos = vec![
+        Mino::from_str("aa").unwrap(),
+        Mino::from_str("b\nb").unwrap(),
+    ];
+    let mut board = Board::from_str_with_minos("aa.\n..b\n#.b", &minos).unwrap();
+    board.put_mino(
+        minos[0].clone(),
+        TransForm::new(0, 1, Orientation::rotations()[0].clone()),
+    );
+    board.insert_column(0);
+    board.insert_row(0);
+    assert_eq!(board.pretty_shape(), "####\n#aa.\n#aab\n##.b");
+    assert_eq!(board.fixed_mino_transforms().len(), 2);
+    assert!(!board.undo());
+    // The new column cuts both 'a' apart and the new row cuts 'b' apart.
+    board.insert_column(2);
+    board.insert_row(3);
+    assert_eq!(board.pretty_shape(), "#####\n#.#..\n#.#..\n#####\n###..");
+    assert_eq!(board.mino_transforms().len(), 0);
+    let mut board =
+        Board::from_str_with_minos("#####\n#.aa#\n#####\n=\n.....\n..BW.\n.....", &minos).unwrap();
+    assert_eq!(board.trim_with_offset(), (1, 1));
+    assert_eq!(board.pretty_shape(), ".aa");
+    assert_eq!(board.target_colors().unwrap().to_lines(), vec![".BW"]);
+    board.shift(-1, 0);
+    assert_eq!(board.pretty_shape(), "aa#");
+    assert_eq!(board.fixed_mino_transforms()[0].1.x(), 0);
+    board.shift(-1, 0);
+    assert_eq!(board.pretty_shape(), ".##");
+}
+
+#[test]
+fn scale_board_pad_trim_test() {
+    use std::str::FromStr;
+    let minos = vec![Mino::from_str("aa").unwrap()];
+    let original = Board::from_str_with_minos(
+        "#aa
+...
+=
+.BW
+WBW",
+        &minos,
+    )
+    .unwrap();
+    let mut board = original.clone();
+    board.pad(1, 2, 3, 4);
+    assert_eq!(board.width(), 9);
+    assert_eq!(board.height(), 6);
+    assert_eq!(board.trim_with_offset(), (4, 1));
+    assert_eq!(board.pretty_shape(), original.pretty_shape());
+    assert_eq!(board.target_colors(), original.target_colors());
+    assert_eq!(
+        board.fixed_mino_transforms(),
+        original.fixed_mino_transforms()
+    );
+}
+
+#[test]
+fn scale_mino_test() {
+    use std::str::FromStr;
+    let mut mino = Mino::from_str("a.\naa\n=\nB.\nWB").unwrap();
+    mino.pad(0, 1, 1, 0);
+    mino.remove_column(0);
+    assert_eq!(mino.shape, Shape::from_str("..\n#.\n..").unwrap());
+    assert_eq!(mino.colors.unwrap().to_lines(), vec!["..", "B.", ".."]);
+}
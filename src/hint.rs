@@ -0,0 +1,96 @@
+use crate::cover::{CoverSearch, Supply};
+use crate::{Board, Mino, TransForm};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What [`Board::hint`] suggests for a position.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Hint {
+    /// Putting this mino here keeps the position solvable.
+    Place(Mino, TransForm),
+    /// Every vacant cell is covered and no mino is left.
+    Solved,
+    /// The position can not be completed, with the reason.
+    Unsolvable(String),
+}
+
+impl Board {
+    /// A placement from one complete solution of the position, keeping the minos already
+    /// on the board where they are and using each of `remaining` exactly once.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino, Orientation, TransForm, hint::Hint};
+    /// let mut board = Board::from_str("...\n...").unwrap();
+    /// let bar = Mino::from_str("aaa").unwrap();
+    /// let at = |y| TransForm::new(0, y, Orientation::rotations()[0].clone());
+    /// board.put_mino(bar.clone(), at(0));
+    /// assert_eq!(board.hint(&[bar.clone()]), Hint::Place(bar.clone(), at(1)));
+    /// board.put_mino(bar, at(1));
+    /// assert_eq!(board.hint(&[]), Hint::Solved);
+    /// ```
+    pub fn hint(&self, remaining: &[Mino]) -> Hint {
+        self.hint_unless_cancelled(remaining, &AtomicBool::new(false))
+            .expect("the search is never cancelled")
+    }
+    /// [`Board::hint`], giving up with `None` once `cancel` is set, so that a search
+    /// on another thread can be dropped when the position changes.
+    pub fn hint_unless_cancelled(&self, remaining: &[Mino], cancel: &AtomicBool) -> Option<Hint> {
+        if remaining.is_empty() && self.shape.count_vacant() == 0 {
+            return Some(Hint::Solved);
+        }
+        if let Some(reason) = self.infeasibility(remaining) {
+            return Some(Hint::Unsolvable(reason));
+        }
+        let supplies: Vec<(Mino, Supply)> = remaining
+            .iter()
+            .map(|mino| (mino.clone(), Supply::exactly(1)))
+            .collect();
+        let solved = CoverSearch::with_supplies(self, &supplies)
+            .with_cancel(cancel)
+            .solve();
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        Some(match solved.map(|s| s.board()) {
+            Some(solved) => {
+                let (mino, transform) =
+                    solved.mino_transforms()[self.mino_transforms().len()].clone();
+                Hint::Place(mino, transform)
+            }
+            None => Hint::Unsolvable("No solution continues from this position".to_string()),
+        })
+    }
+}
+
+#[test]
+fn test_hint_leads_to_solution() {
+    use std::str::FromStr;
+    let minos = Mino::minos_from_path("data/minos").unwrap();
+    let mut board = Board::from_text_path("data/board.txt").unwrap();
+    let mut remaining = minos.clone();
+    while let Hint::Place(mino, transform) = board.hint(&remaining) {
+        assert!(board.can_put(&mino, &transform));
+        let index = remaining.iter().position(|m| *m == mino).unwrap();
+        remaining.remove(index);
+        board.put_mino(mino, transform);
+    }
+    assert_eq!(board.hint(&remaining), Hint::Solved);
+    assert_eq!(board.mino_transforms().len(), minos.len());
+    // A bar across the middle of a 3x3 board leaves two rows which L-trominoes can not fill.
+    let mut board = Board::from_str("...\n...\n...").unwrap();
+    let bar = Mino::from_str("aaa").unwrap();
+    board.put_mino(
+        bar,
+        TransForm {
+            x: 0,
+            y: 1,
+            rotation: crate::Rotation::Neutral,
+            flipped: false,
+        },
+    );
+    let minos = vec![Mino::from_str("cc\n.c").unwrap(); 2];
+    assert!(matches!(board.hint(&minos), Hint::Unsolvable(_)));
+    let cancelled = AtomicBool::new(true);
+    assert_eq!(board.hint_unless_cancelled(&minos, &cancelled), None);
+}
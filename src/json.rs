@@ -0,0 +1,241 @@
+use crate::cover::Supply;
+use crate::{Board, Mino, symmetry};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Whether `path` is read and written as JSON rather than text, by its extension.
+pub(crate) fn is_json<P>(path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    path.as_ref()
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+}
+
+/// Minos of a JSON minos file, without warning about duplicate shapes.
+pub(crate) fn read_json_supplies(s: &str) -> Result<Vec<(Mino, Supply)>, String> {
+    let minos: Vec<Mino> = serde_json::from_str(s).map_err(|e| e.to_string())?;
+    for mino in &minos {
+        Mino::from_str(&mino.to_lines().join("\n"))?;
+    }
+    Ok(minos
+        .chunk_by(|a, b| a == b)
+        .map(|chunk| (chunk[0].clone(), Supply::exactly(chunk.len())))
+        .collect())
+}
+
+impl Board {
+    /// A board serialized with `serde_json`, like `data/board.json`. Minos in
+    /// `mino_transforms` are put back on the board, so that a partly solved board
+    /// can be resumed.
+    ///
+    /// ```
+    /// use tiling_mino_solver::Board;
+    /// let s = r#"{
+    ///     "shape": [[false, false, false], [true, false, false]],
+    ///     "mino_transforms": [
+    ///         [{"name": "a", "shape": [[true, true]]}, {"x": 1, "y": 1, "rotation": "Neutral"}]
+    ///     ]
+    /// }"#;
+    /// let board = Board::from_json_str(s).unwrap();
+    /// assert_eq!(board.pretty_shape(), "...\n#aa");
+    /// ```
+    pub fn from_json_str(s: &str) -> Result<Self, String> {
+        let mut board: Self = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        let width = board.width();
+        if board.shape.0.iter().any(|row| row.len() != width) {
+            return Err("The rows of the board are not of the same width".to_string());
+        }
+        if let Some(colors) = board.target_colors.take() {
+            board.set_target_colors(colors)?;
+        }
+        if board.fixed > board.mino_transforms.len() {
+            return Err(format!(
+                "{} minos are fixed, but only {} are on the board",
+                board.fixed,
+                board.mino_transforms.len()
+            ));
+        }
+        let mut covered = HashSet::new();
+        for (mino, transform) in &board.mino_transforms {
+            let cells = board
+                .covered_cells(&mino.oriented(&transform.orientation()), transform)
+                .ok_or(format!("Mino '{}' is outside the board", mino.name))?;
+            for cell in cells {
+                if !covered.insert(cell) {
+                    return Err(format!(
+                        "Mino '{}' overlaps another mino at {:?}",
+                        mino.name, cell
+                    ));
+                }
+            }
+        }
+        for (x, y) in covered {
+            board.shape.put_on(x, y, true);
+        }
+        Ok(board)
+    }
+    pub fn from_json_path<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let buf = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_json_str(&buf)
+    }
+    /// [`Board::from_json_path`] for `.json` files, [`Board::from_text_path`] otherwise.
+    pub fn from_path<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        if is_json(&path) {
+            Self::from_json_path(path)
+        } else {
+            Self::from_text_path(path)
+        }
+    }
+    /// Like [`Board::from_path`], but letters in text files are minos from `minos`
+    /// already on the board. JSON files name their minos in `mino_transforms`.
+    pub fn from_path_with_minos<P>(path: P, minos: &[Mino]) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        if is_json(&path) {
+            Self::from_json_path(path)
+        } else {
+            Self::from_text_path_with_minos(path, minos)
+        }
+    }
+    /// Inverse of [`Board::from_str_with_minos`]: the grid with minos as letters,
+    /// then the target colours if there are any.
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.pretty_shape().lines().map(String::from).collect();
+        if let Some(colors) = &self.target_colors {
+            lines.push("=".to_string());
+            lines.extend(colors.to_lines());
+        }
+        lines
+    }
+    /// Write the board to `path`, as JSON with its `mino_transforms` if the extension
+    /// is `json` and as [`Board::to_lines`] otherwise.
+    pub fn save_to_path<P>(&self, path: P) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        let text = if is_json(&path) {
+            serde_json::to_string_pretty(self).map_err(|e| e.to_string())?
+        } else {
+            self.to_lines().join("\n")
+        };
+        fs::write(path, text + "\n").map_err(|e| e.to_string())
+    }
+}
+
+impl Mino {
+    /// A list of minos serialized with `serde_json`, like `data/minos.json`.
+    /// Each entry is one copy; consecutive equal entries share a [`Supply`].
+    pub fn supplies_from_json_path<P>(path: P) -> Result<Vec<(Self, Supply)>, String>
+    where
+        P: AsRef<Path>,
+    {
+        let buf = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let supplies =
+            read_json_supplies(&buf).map_err(|e| format!("{}: {}", path.as_ref().display(), e))?;
+        symmetry::warn_duplicate_shapes(&supplies);
+        Ok(supplies)
+    }
+    pub fn minos_from_json_path<P>(path: P) -> Result<Vec<Self>, String>
+    where
+        P: AsRef<Path>,
+    {
+        Self::minos_from_supplies(Self::supplies_from_json_path(path)?)
+    }
+    /// Write `minos` to `path`, as a JSON list if the extension is `json`
+    /// and as [`Mino::minos_to_lines`] otherwise.
+    pub fn save_minos_to_path<P>(path: P, minos: &[Mino]) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        let text = if is_json(&path) {
+            serde_json::to_string_pretty(minos).map_err(|e| e.to_string())?
+        } else {
+            Self::minos_to_lines(minos).join("\n")
+        };
+        fs::write(path, text + "\n").map_err(|e| e.to_string())
+    }
+    /// Write `supplies` to `path`: as a JSON list of copies if the extension is `json`,
+    /// which needs exact counts, in the version 2 format if a mino has a label, and as
+    /// [`Mino::supplies_to_lines`] otherwise.
+    pub fn save_supplies_to_path<P>(path: P, supplies: &[(Mino, Supply)]) -> Result<(), String>
+    where
+        P: AsRef<Path>,
+    {
+        if is_json(&path) {
+            return Self::save_minos_to_path(path, &Self::minos_from_supplies(supplies.to_vec())?);
+        }
+        let lines = if supplies.iter().any(|(mino, _)| mino.label.is_some()) {
+            Self::supplies_to_v2_lines(supplies)
+        } else {
+            Self::supplies_to_lines(supplies)
+        };
+        fs::write(path, lines.join("\n") + "\n").map_err(|e| e.to_string())
+    }
+}
+
+#[test]
+fn test_json_files_match_text_files() {
+    for dir in ["data", "testdata"] {
+        assert_eq!(
+            Board::from_path(format!("{}/board.json", dir)).unwrap(),
+            Board::from_path(format!("{}/board.txt", dir)).unwrap()
+        );
+        assert_eq!(
+            Mino::minos_from_path(format!("{}/minos.json", dir)).unwrap(),
+            Mino::minos_from_path(format!("{}/minos.txt", dir)).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_resume_board_from_json() {
+    let dir = std::env::temp_dir().join(format!("json-resume-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let minos = Mino::minos_from_path("data/minos.json").unwrap();
+    let board = Board::from_path("data/board.json").unwrap();
+    let solved = board.tile_parallel(&minos).unwrap();
+    let mut partial = board.clone();
+    for (mino, transform) in &solved.mino_transforms()[..3] {
+        partial.put_mino(mino.clone(), transform.clone());
+    }
+    partial.save_to_path(dir.join("board.json")).unwrap();
+    let resumed = Board::from_path_with_minos(dir.join("board.json"), &minos).unwrap();
+    assert_eq!(resumed, partial);
+    assert_eq!(
+        resumed.unplaced_minos(&minos).unwrap().len(),
+        minos.len() - 3
+    );
+    partial.save_to_path(dir.join("board.txt")).unwrap();
+    let from_text = Board::from_path_with_minos(dir.join("board.txt"), &minos).unwrap();
+    assert_eq!(from_text.pretty_shape(), partial.pretty_shape());
+    Mino::save_minos_to_path(dir.join("minos.json"), &minos).unwrap();
+    assert_eq!(
+        Mino::minos_from_path(dir.join("minos.json")).unwrap(),
+        minos
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_invalid_json_board() {
+    let outside = r#"{"shape": [[false]], "mino_transforms": [
+        [{"name": "a", "shape": [[true, true]]}, {"x": 0, "y": 0, "rotation": "Neutral"}]
+    ]}"#;
+    assert_eq!(
+        Board::from_json_str(outside).unwrap_err(),
+        "Mino 'a' is outside the board"
+    );
+    let ragged = r#"{"shape": [[false], [false, false]], "mino_transforms": []}"#;
+    assert!(Board::from_json_str(ragged).is_err());
+}
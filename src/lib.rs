@@ -1,29 +1,117 @@
+use colors::{Colors, split_colors};
+use constraints::Constraints;
+use cover::Supply;
+use directory::DirectoryOptions;
 use nu_ansi_term::{AnsiGenericStrings, Color};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
 use std::path::Path;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
 };
+use topology::Topology;
 use tracing::info;
+pub mod batch;
+pub mod catalog;
+pub mod colors;
+pub mod constraints;
+pub mod cover;
+pub mod diagnosis;
+pub mod directory;
+pub mod editor;
+pub mod geometry;
 pub mod gui;
+pub mod hint;
+pub mod json;
+pub mod packing;
+pub mod parity;
+pub mod piece_format;
+pub mod play;
+pub mod polyomino;
+pub mod puzzle;
+pub mod symmetry;
+pub mod topology;
 
-#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Board {
     pub shape: Shape,
     mino_transforms: Vec<(Mino, TransForm)>,
+    /// Number of leading `mino_transforms` which came with the board.
+    #[serde(default)]
+    fixed: usize,
+    /// Colours the minos have to show on the board cells.
+    #[serde(default)]
+    target_colors: Option<Colors>,
+    #[serde(default)]
+    topology: Topology,
+    /// Edits by [`Board::put_mino`] and [`Board::remove_mino`], latest last.
+    #[serde(skip)]
+    undo_stack: Vec<Edit>,
+    /// Edits taken back by [`Board::undo`], latest last.
+    #[serde(skip)]
+    redo_stack: Vec<Edit>,
 }
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+/// Boards are equal when the same minos are in the same places,
+/// whatever their edit history.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape
+            && self.mino_transforms == other.mino_transforms
+            && self.fixed == other.fixed
+            && self.target_colors == other.target_colors
+            && self.topology == other.topology
+    }
+}
+
+impl Eq for Board {}
+
+/// A change of the minos on a board, which can be undone and redone.
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Edit {
+    /// A mino was put on the board, last in `mino_transforms`.
+    Put(Mino, TransForm),
+    /// The mino at `index` of `mino_transforms` was taken off, and was fixed or not.
+    Remove(usize, Mino, TransForm, bool),
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
 pub struct TransForm {
     x: usize,
     y: usize,
     rotation: Rotation,
+    #[serde(default)]
+    flipped: bool,
 }
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+impl TransForm {
+    pub fn new(x: usize, y: usize, orientation: Orientation) -> Self {
+        Self {
+            x,
+            y,
+            rotation: orientation.rotation,
+            flipped: orientation.flipped,
+        }
+    }
+    pub fn x(&self) -> usize {
+        self.x
+    }
+    pub fn y(&self) -> usize {
+        self.y
+    }
+    pub fn orientation(&self) -> Orientation {
+        Orientation {
+            rotation: self.rotation.clone(),
+            flipped: self.flipped,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub enum Rotation {
     Neutral,
     Left,
@@ -31,6 +119,80 @@ pub enum Rotation {
     OneEighty,
 }
 
+impl Rotation {
+    pub const ALL: [Rotation; 4] = [
+        Rotation::Neutral,
+        Rotation::Left,
+        Rotation::Right,
+        Rotation::OneEighty,
+    ];
+}
+
+/// A rotation of a mino, which is mirrored left to right first when `flipped`.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
+pub struct Orientation {
+    pub rotation: Rotation,
+    pub flipped: bool,
+}
+
+impl Orientation {
+    /// The four rotations without flipping.
+    pub fn rotations() -> Vec<Self> {
+        Rotation::ALL
+            .into_iter()
+            .map(|rotation| Self {
+                rotation,
+                flipped: false,
+            })
+            .collect()
+    }
+    /// The four rotations, with and without flipping.
+    pub fn all() -> Vec<Self> {
+        [false, true]
+            .into_iter()
+            .flat_map(|flipped| {
+                Rotation::ALL
+                    .into_iter()
+                    .map(move |rotation| Self { rotation, flipped })
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Orientation {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (flipped, rotation) = match s.strip_prefix("flipped_") {
+            Some(rotation) => (true, rotation),
+            None => (false, s),
+        };
+        let rotation = match rotation {
+            "neutral" => Rotation::Neutral,
+            "left" => Rotation::Left,
+            "right" => Rotation::Right,
+            "one_eighty" => Rotation::OneEighty,
+            _ => return Err(format!("Unknown orientation '{}'", s)),
+        };
+        Ok(Self { rotation, flipped })
+    }
+}
+
+impl fmt::Display for Orientation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rotation = match self.rotation {
+            Rotation::Neutral => "neutral",
+            Rotation::Left => "left",
+            Rotation::Right => "right",
+            Rotation::OneEighty => "one_eighty",
+        };
+        if self.flipped {
+            write!(f, "flipped_{}", rotation)
+        } else {
+            write!(f, "{}", rotation)
+        }
+    }
+}
+
 #[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
 pub enum CellState {
     Wall,
@@ -63,6 +225,11 @@ impl Board {
         Self {
             shape,
             mino_transforms: vec![],
+            fixed: 0,
+            target_colors: None,
+            topology: Topology::default(),
+            undo_stack: vec![],
+            redo_stack: vec![],
         }
     }
     pub fn from_text_path<P>(path: P) -> Result<Self, String>
@@ -72,6 +239,181 @@ impl Board {
         let buf = fs::read_to_string(path).map_err(|e| e.to_string())?;
         Self::from_str(&buf)
     }
+    pub fn from_text_path_with_minos<P>(path: P, minos: &[Mino]) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let buf = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        Self::from_str_with_minos(&buf, minos)
+    }
+    /// Like [`Board::from_str`], but letters are pieces already on the board.
+    /// Each 4-connected group of a letter must be a rotation of a mino with that name
+    /// in `minos`, and is put on the board as a fixed entry of `mino_transforms`.
+    /// A group of touching copies is split into rotations of the mino when there is
+    /// exactly one way to do so; otherwise a JSON board, see [`Board::from_json_str`],
+    /// can fix them.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino};
+    /// let minos = vec![Mino::from_str("a.\naa").unwrap(), Mino::from_str("bbb").unwrap()];
+    /// let board = Board::from_str_with_minos("#aa\n..a", &minos).unwrap();
+    /// assert_eq!(board.fixed_mino_transforms().len(), 1);
+    /// assert_eq!(board.unplaced_minos(&minos).unwrap(), vec![minos[1].clone()]);
+    /// ```
+    pub fn from_str_with_minos(s: &str, minos: &[Mino]) -> Result<Self, String> {
+        let (grid, colors) = split_colors(s);
+        let rows: Vec<Vec<char>> = grid
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().collect())
+            .collect();
+        let walls = rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&c| if c == '#' { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let mut board = Self::from_str(&walls)?;
+        if let Some(colors) = colors {
+            board.set_target_colors(Colors::from_str(&colors)?)?;
+        }
+        let mut labels: Vec<char> = rows
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|&c| c != '.' && c != '#')
+            .collect();
+        labels.sort();
+        labels.dedup();
+        for label in labels {
+            for cells in label_groups(&rows, label) {
+                board.put_fixed_mino(label, &cells, minos)?;
+            }
+        }
+        board.fixed = board.mino_transforms.len();
+        Ok(board)
+    }
+    fn put_fixed_mino(
+        &mut self,
+        label: char,
+        cells: &[(usize, usize)],
+        minos: &[Mino],
+    ) -> Result<(), String> {
+        let min_x = cells.iter().map(|&(x, _)| x).min().unwrap_or_default();
+        let min_y = cells.iter().map(|&(_, y)| y).min().unwrap_or_default();
+        let width = cells
+            .iter()
+            .map(|&(x, _)| x - min_x + 1)
+            .max()
+            .unwrap_or_default();
+        let height = cells
+            .iter()
+            .map(|&(_, y)| y - min_y + 1)
+            .max()
+            .unwrap_or_default();
+        let mut shape = Shape(vec![vec![false; width]; height]);
+        for &(x, y) in cells {
+            shape.put_on(x - min_x, y - min_y, true);
+        }
+        let named: Vec<&Mino> = minos.iter().filter(|m| m.name == label).collect();
+        if named.is_empty() {
+            return Err(format!("No mino named '{}' for the board", label));
+        }
+        let matches: Vec<(&Mino, TransForm)> = named
+            .iter()
+            .copied()
+            .flat_map(|m| {
+                m.orientations()
+                    .into_iter()
+                    .filter(|o| m.oriented(o).shape == shape)
+                    .map(move |o| {
+                        let transform = TransForm {
+                            x: min_x,
+                            y: min_y,
+                            rotation: o.rotation,
+                            flipped: o.flipped,
+                        };
+                        (m, transform)
+                    })
+            })
+            .collect();
+        if matches.is_empty() {
+            // Touching copies form one group, which is put on as its pieces.
+            return match split_into_copies(cells, &named).as_slice() {
+                [pieces] if pieces.len() > 1 => pieces
+                    .iter()
+                    .try_for_each(|piece| self.put_fixed_mino(label, piece, minos)),
+                [] | [_] => Err(format!(
+                    "Cells of '{}' at ({}, {}) do not match the shape of the mino",
+                    label, min_x, min_y
+                )),
+                _ => Err(format!(
+                    "Cells of '{}' at ({}, {}) can be split into copies of the mino in more \
+                    than one way; use a JSON board to fix the copies",
+                    label, min_x, min_y
+                )),
+            };
+        }
+        let matches: Vec<(&Mino, TransForm)> = matches
+            .into_iter()
+            .filter(|(m, _)| m.constraints.allows(self, cells))
+            .collect();
+        if matches.is_empty() {
+            return Err(format!(
+                "Cells of '{}' at ({}, {}) break the constraints of the mino",
+                label, min_x, min_y
+            ));
+        }
+        let (mino, transform) = matches
+            .into_iter()
+            .find(|(m, t)| self.can_put(m, t))
+            .ok_or(format!(
+                "Cells of '{}' at ({}, {}) do not match the target colors",
+                label, min_x, min_y
+            ))?;
+        self.push_mino(mino.clone(), transform);
+        Ok(())
+    }
+    /// Minos put on the board by [`Board::from_str_with_minos`].
+    pub fn fixed_mino_transforms(&self) -> &[(Mino, TransForm)] {
+        &self.mino_transforms[..self.fixed]
+    }
+    /// `minos` without the ones already on the board.
+    pub fn unplaced_minos(&self, minos: &[Mino]) -> Result<Vec<Mino>, String> {
+        let mut unplaced = minos.to_vec();
+        for (mino, _) in &self.mino_transforms {
+            let index = unplaced.iter().position(|m| m == mino).ok_or(format!(
+                "Mino '{}' is on the board too many times",
+                mino.name
+            ))?;
+            unplaced.remove(index);
+        }
+        Ok(unplaced)
+    }
+    /// `supplies` reduced by the minos already on the board.
+    pub fn unplaced_supplies(
+        &self,
+        supplies: &[(Mino, Supply)],
+    ) -> Result<Vec<(Mino, Supply)>, String> {
+        let mut unplaced = supplies.to_vec();
+        for (mino, _) in &self.mino_transforms {
+            let supply = unplaced
+                .iter_mut()
+                .find(|(m, supply)| m == mino && supply.max != Some(0))
+                .map(|(_, supply)| supply)
+                .ok_or(format!(
+                    "Mino '{}' is on the board too many times",
+                    mino.name
+                ))?;
+            supply.min = supply.min.saturating_sub(1);
+            supply.max = supply.max.map(|max| max - 1);
+        }
+        Ok(unplaced)
+    }
     pub fn height(&self) -> usize {
         self.shape.height()
     }
@@ -81,10 +423,43 @@ impl Board {
     pub fn is_wall(&self, x: usize, y: usize) -> bool {
         self.shape.is_wall(x, y)
     }
+    pub fn mino_transforms(&self) -> &[(Mino, TransForm)] {
+        &self.mino_transforms
+    }
+    pub fn target_colors(&self) -> Option<&Colors> {
+        self.target_colors.as_ref()
+    }
+    pub fn set_target_colors(&mut self, colors: Colors) -> Result<(), String> {
+        if colors.width() != self.width() || colors.height() != self.height() {
+            return Err(format!(
+                "Target colors are {}x{}, but the board is {}x{}",
+                colors.width(),
+                colors.height(),
+                self.width(),
+                self.height()
+            ));
+        }
+        self.target_colors = Some(colors);
+        Ok(())
+    }
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.topology = topology;
+    }
     pub fn tile_parallel(&self, minos: &[Mino]) -> Option<Self> {
-        if minos.len() > 8 {
-            self.pretty_print();
-            println!("{}", "-".repeat(self.width()));
+        self.tile_search(minos, &AtomicUsize::new(0), true)
+    }
+    /// [`Board::tile_parallel`] without printing progress, adding the number of
+    /// searched nodes to `nodes`.
+    pub fn tile_counting(&self, minos: &[Mino], nodes: &AtomicUsize) -> Option<Self> {
+        self.tile_search(minos, nodes, false)
+    }
+    fn tile_search(&self, minos: &[Mino], nodes: &AtomicUsize, progress: bool) -> Option<Self> {
+        nodes.fetch_add(1, Ordering::Relaxed);
+        if progress && minos.len() > 8 {
+            self.log_progress();
         }
         if minos.is_empty() {
             return Some(self.clone());
@@ -96,82 +471,275 @@ impl Board {
         } else {
             ts.into_par_iter().find_map_any(|t| {
                 let mut new_board: Board = self.clone();
-                new_board.put_mino(head_mino.clone(), t);
-                new_board.tile_parallel(&minos[1..])
+                new_board.push_mino(head_mino.clone(), t);
+                new_board.tile_search(&minos[1..], nodes, progress)
             })
         }
     }
+    /// Log the board of a search step, keeping stdout for the results.
+    fn log_progress(&self) {
+        for line in self.pretty_shape().lines() {
+            info!("{}", line);
+        }
+        info!("{}", "-".repeat(self.width()));
+    }
     #[allow(dead_code)]
     fn tile_serial(&self, minos: &[Mino]) -> Option<Self> {
+        let mut board = self.clone();
+        board.tile_in_place(minos).then_some(board)
+    }
+    /// Depth first search putting and taking back minos on this board instead of
+    /// cloning it at every node. The board is left tiled when it succeeds.
+    fn tile_in_place(&mut self, minos: &[Mino]) -> bool {
         if minos.len() > 8 {
-            self.pretty_print();
-            info!("{}", "-".repeat(self.width()));
+            self.log_progress();
         }
-        if minos.is_empty() {
-            return Some(self.clone());
-        }
-        let head_mino = minos[0].clone();
-        let ts = self.search_can_put(&head_mino);
-        if ts.is_empty() {
-            None
-        } else {
-            ts.into_iter().find_map(|t| {
-                let mut new_board: Board = self.clone();
-                new_board.put_mino(head_mino.clone(), t);
-                new_board.tile_serial(&minos[1..])
-            })
+        let Some((head_mino, rest)) = minos.split_first() else {
+            return true;
+        };
+        for t in self.search_can_put(head_mino) {
+            self.push_mino(head_mino.clone(), t);
+            if self.tile_in_place(rest) {
+                return true;
+            }
+            self.unplace_mino(self.mino_transforms.len() - 1);
         }
+        false
     }
     fn search_can_put(&self, mino: &Mino) -> Vec<TransForm> {
         let mut transforms = vec![];
-        for r in [
-            Rotation::Neutral,
-            Rotation::Left,
-            Rotation::Right,
-            Rotation::OneEighty,
-        ] {
-            transforms.extend(self.search_can_put_rotated(&mino.rotated(&r), &r));
+        for o in mino.orientations() {
+            transforms.extend(self.search_can_put_oriented(&mino.oriented(&o), &o));
+        }
+        if self.topology.twists_x() {
+            // Anchors beyond the right edge give the placements of the mirrored
+            // orientations, so only the ones no other anchor covers are kept.
+            let cells_of = |t: &TransForm| {
+                let mut cells = self
+                    .covered_cells(&mino.oriented(&t.orientation()), t)
+                    .unwrap_or_default();
+                cells.sort();
+                cells
+            };
+            let mut seen: HashSet<Vec<(usize, usize)>> = transforms
+                .iter()
+                .filter(|t| t.x < self.width())
+                .map(cells_of)
+                .collect();
+            transforms.retain(|t| t.x < self.width() || seen.insert(cells_of(t)));
         }
         transforms
     }
-    fn search_can_put_rotated(&self, rotated_mino: &Mino, rotation: &Rotation) -> Vec<TransForm> {
+    fn search_can_put_oriented(
+        &self,
+        rotated_mino: &Mino,
+        orientation: &Orientation,
+    ) -> Vec<TransForm> {
         let mut transforms = vec![];
-        for y in 0..=self.height() - rotated_mino.height() {
-            for x in 0..=self.width() - rotated_mino.width() {
+        let ys = if self.topology.wraps_y() {
+            0..self.height()
+        } else {
+            0..(self.height() + 1).saturating_sub(rotated_mino.height())
+        };
+        let xs = if self.topology.wraps_x() {
+            0..self.width()
+        } else {
+            0..(self.width() + 1).saturating_sub(rotated_mino.width())
+        };
+        // On a twisted board, x in `width..2 * width` is the board once more, mirrored
+        // top to bottom: anchors there cross the twisted edge from the left, or put
+        // the mino mirrored, as sliding it once around the board does.
+        let twisted_xs = if self.topology.twists_x() {
+            self.width()..2 * self.width()
+        } else {
+            0..0
+        };
+        for y in ys {
+            for x in xs.clone().chain(twisted_xs.clone()) {
                 let t = TransForm {
                     x,
                     y,
-                    rotation: rotation.clone(),
+                    rotation: orientation.rotation.clone(),
+                    flipped: orientation.flipped,
                 };
-                if self.can_put(rotated_mino, &t) {
+                let allowed = self
+                    .placeable_cells(rotated_mino, &t)
+                    .is_some_and(|cells| rotated_mino.constraints.allows(self, &cells));
+                if allowed {
                     transforms.push(t);
                 }
             }
         }
         transforms
     }
+    /// Board cells covered by an already oriented mino anchored at `transform`,
+    /// following the board topology across glued edges.
+    /// `None` if the mino falls off the board or overlaps itself after wrapping.
+    fn covered_cells(
+        &self,
+        rotated_mino: &Mino,
+        transform: &TransForm,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut cells = vec![];
+        for (mino_x, mino_y, b) in rotated_mino.shape.coordinates() {
+            if !b {
+                continue;
+            }
+            let cell = self.topology.wrap(
+                transform.x + mino_x,
+                transform.y + mino_y,
+                self.width(),
+                self.height(),
+            )?;
+            if cells.contains(&cell) {
+                return None;
+            }
+            cells.push(cell);
+        }
+        Some(cells)
+    }
+    /// Cells covered by an already oriented mino if they are vacant
+    /// and its colours match the target colours of the board.
+    fn placeable_cells(
+        &self,
+        oriented_mino: &Mino,
+        transform: &TransForm,
+    ) -> Option<Vec<(usize, usize)>> {
+        let cells = self.covered_cells(oriented_mino, transform)?;
+        if cells.iter().any(|&(x, y)| self.is_wall(x, y)) {
+            return None;
+        }
+        if let (Some(colors), Some(targets)) = (&oriented_mino.colors, &self.target_colors) {
+            let mino_cells = oriented_mino
+                .shape
+                .coordinates()
+                .into_iter()
+                .filter(|&(_, _, b)| b);
+            for ((mino_x, mino_y, _), &(x, y)) in mino_cells.zip(&cells) {
+                if let (Some(c), Some(target)) = (colors.get(mino_x, mino_y), targets.get(x, y))
+                    && c != target
+                {
+                    return None;
+                }
+            }
+        }
+        Some(cells)
+    }
+    /// Whether `mino`, oriented by `transform`, fits on vacant cells
+    /// with its colours matching the target colours.
     pub fn can_put(&self, mino: &Mino, transform: &TransForm) -> bool {
-        mino.shape
-            .coordinates()
-            .into_iter()
-            .all(|(mino_x, mino_y, b)| {
-                let x = transform.x + mino_x;
-                let y = transform.y + mino_y;
-                !(self.is_wall(x, y) && b)
-            })
+        self.placeable_cells(&mino.oriented(&transform.orientation()), transform)
+            .is_some()
     }
 
+    /// Put `mino` oriented by `transform` on the board. Can be taken back by [`Board::undo`].
     pub fn put_mino(&mut self, mino: Mino, transform: TransForm) {
-        mino.rotated(&transform.rotation)
-            .shape
-            .coordinates()
+        self.place_mino(
+            self.mino_transforms.len(),
+            mino.clone(),
+            transform.clone(),
+            false,
+        );
+        self.undo_stack.push(Edit::Put(mino, transform));
+        self.redo_stack.clear();
+    }
+    /// Take the `index`-th mino of [`Board::mino_transforms`] off the board,
+    /// making its cells vacant again. Can be taken back by [`Board::undo`].
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino, Orientation, TransForm};
+    /// let empty = Board::from_str("...\n...").unwrap();
+    /// let mut board = empty.clone();
+    /// let bar = Mino::from_str("aaa").unwrap();
+    /// let at = |y| TransForm::new(0, y, Orientation::rotations()[0].clone());
+    /// board.put_mino(bar.clone(), at(0));
+    /// board.put_mino(bar.clone(), at(1));
+    /// assert_eq!(board.remove_mino(0), (bar, at(0)));
+    /// assert_eq!(board.pretty_shape(), "...\naaa");
+    /// assert!(board.undo() && board.undo() && board.undo() && !board.undo());
+    /// assert_eq!(board, empty);
+    /// assert!(board.redo() && board.redo());
+    /// assert_eq!(board.pretty_shape(), "aaa\naaa");
+    /// ```
+    pub fn remove_mino(&mut self, index: usize) -> (Mino, TransForm) {
+        let fixed = index < self.fixed;
+        let (mino, transform) = self.unplace_mino(index);
+        self.undo_stack
+            .push(Edit::Remove(index, mino.clone(), transform.clone(), fixed));
+        self.redo_stack.clear();
+        (mino, transform)
+    }
+    /// Take back the last [`Board::put_mino`] or [`Board::remove_mino`].
+    /// Returns `false` when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo_stack.pop() else {
+            return false;
+        };
+        match &edit {
+            Edit::Put(..) => {
+                self.unplace_mino(self.mino_transforms.len() - 1);
+            }
+            Edit::Remove(index, mino, transform, fixed) => {
+                self.place_mino(*index, mino.clone(), transform.clone(), *fixed)
+            }
+        }
+        self.redo_stack.push(edit);
+        true
+    }
+    /// Do again the last edit taken back by [`Board::undo`].
+    /// Returns `false` when there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo_stack.pop() else {
+            return false;
+        };
+        match &edit {
+            Edit::Put(mino, transform) => self.place_mino(
+                self.mino_transforms.len(),
+                mino.clone(),
+                transform.clone(),
+                false,
+            ),
+            Edit::Remove(index, ..) => {
+                self.unplace_mino(*index);
+            }
+        }
+        self.undo_stack.push(edit);
+        true
+    }
+    /// Put a mino at `index` of `mino_transforms` without recording it in the history.
+    fn place_mino(&mut self, index: usize, mino: Mino, transform: TransForm, fixed: bool) {
+        self.covered_cells(&mino.oriented(&transform.orientation()), &transform)
+            .expect("mino must be inside the board")
             .into_iter()
-            .for_each(|(mino_x, mino_y, mino_b)| {
-                let x = transform.x + mino_x;
-                let y = transform.y + mino_y;
-                self.shape.put_on(x, y, mino_b);
-            });
-        self.mino_transforms.push((mino, transform));
+            .for_each(|(x, y)| self.shape.put_on(x, y, true));
+        self.mino_transforms.insert(index, (mino, transform));
+        if fixed {
+            self.fixed += 1;
+        }
+    }
+    /// Put a mino last on the board without recording it in the history, for searches.
+    pub(crate) fn push_mino(&mut self, mino: Mino, transform: TransForm) {
+        self.place_mino(self.mino_transforms.len(), mino, transform, false);
+    }
+    /// Inverse of [`Board::place_mino`], without recording it in the history.
+    pub(crate) fn unplace_mino(&mut self, index: usize) -> (Mino, TransForm) {
+        let (mino, transform) = self.mino_transforms.remove(index);
+        self.covered_cells(&mino.oriented(&transform.orientation()), &transform)
+            .expect("mino must be inside the board")
+            .into_iter()
+            .for_each(|(x, y)| self.shape.0[y][x] = false);
+        if index < self.fixed {
+            self.fixed -= 1;
+        }
+        (mino, transform)
+    }
+    /// Index in [`Board::mino_transforms`] of the mino covering the cell at `(x, y)`.
+    pub fn mino_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.mino_transforms.iter().position(|(mino, transform)| {
+            self.covered_cells(&mino.oriented(&transform.orientation()), transform)
+                .is_some_and(|cells| cells.contains(&(x, y)))
+        })
     }
     const MINO_COLORS: [Color; 6] = [
         Color::Blue,
@@ -181,22 +749,30 @@ impl Board {
         Color::Red,
         Color::Yellow,
     ];
-    pub fn pretty_print(&self) {
-        let mino_chars: HashMap<char, Color> = self
-            .mino_transforms
+    /// Colours of placed minos by name, as printed by [`Board::pretty_print`].
+    /// A mino's own display colour comes first.
+    pub(crate) fn mino_colors(&self) -> HashMap<char, Color> {
+        self.mino_transforms
             .iter()
             .enumerate()
             .map(|(ind, t)| {
+                let color =
+                    t.0.display_color
+                        .as_deref()
+                        .and_then(|c| piece_format::parse_color(c).ok());
                 (
                     t.0.name,
-                    if ind < 6 {
-                        Self::MINO_COLORS[ind]
-                    } else {
-                        Color::White
+                    match color {
+                        Some(color) => color,
+                        None if ind < 6 => Self::MINO_COLORS[ind],
+                        None => Color::White,
                     },
                 )
             })
-            .collect();
+            .collect()
+    }
+    pub fn pretty_print(&self) {
+        let mino_chars = self.mino_colors();
         let mut n_vacant = 0;
         let mut pp = vec![];
         for c in self.pretty_shape().chars() {
@@ -226,17 +802,10 @@ impl Board {
             }
         });
         for (mino, transform) in &self.mino_transforms {
-            mino.rotated(&transform.rotation)
-                .shape
-                .coordinates()
+            self.covered_cells(&mino.oriented(&transform.orientation()), transform)
+                .unwrap_or_default()
                 .into_iter()
-                .for_each(|(mino_x, mino_y, mino_b)| {
-                    let x = transform.x + mino_x;
-                    let y = transform.y + mino_y;
-                    if mino_b {
-                        char_matrix[y][x] = mino.name;
-                    }
-                });
+                .for_each(|(x, y)| char_matrix[y][x] = mino.name);
         }
         char_matrix
     }
@@ -261,42 +830,187 @@ impl Board {
 
 impl FromStr for Board {
     type Err = String;
+    /// Rows of '.' and '#', optionally followed by a line of '=' and target colours.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.chars().any(|c| c != '.' && c != '#' && c != '\n') {
+        let (grid, colors) = split_colors(s);
+        if grid.chars().any(|c| c != '.' && c != '#' && c != '\n') {
             return Err(format!(
                 "'.' or '#' can be used for board initialization. Building board failed for '{}'",
                 s,
             ));
         };
-        Ok(Self {
-            shape: Shape::from_str(s)?,
-            mino_transforms: vec![],
+        let mut board = Self::new(Shape::from_str(&grid)?);
+        if let Some(colors) = colors {
+            board.set_target_colors(Colors::from_str(&colors)?)?;
+        }
+        Ok(board)
+    }
+}
+
+/// 4-connected groups of the cells labeled `label`.
+/// Ways to cover `cells` exactly with rotations of `minos`, each as the cells of
+/// its pieces. The search stops at the second way, as only a unique one is used.
+fn split_into_copies(cells: &[(usize, usize)], minos: &[&Mino]) -> Vec<Vec<Vec<(usize, usize)>>> {
+    let mut shapes: Vec<Vec<(usize, usize)>> = minos
+        .iter()
+        .flat_map(|m| {
+            m.orientations().into_iter().map(|o| {
+                m.oriented(&o)
+                    .shape
+                    .coordinates()
+                    .into_iter()
+                    .filter(|&(_, _, b)| b)
+                    .map(|(x, y, _)| (x, y))
+                    .collect::<Vec<(usize, usize)>>()
+            })
         })
+        .filter(|shape| !shape.is_empty())
+        .collect();
+    shapes.sort();
+    shapes.dedup();
+    // Cells as (y, x), so that the first one left is the top left.
+    let mut left: BTreeSet<(usize, usize)> = cells.iter().map(|&(x, y)| (y, x)).collect();
+    let mut splits = vec![];
+    split_cells(&mut left, &shapes, &mut vec![], &mut splits);
+    splits
+}
+
+/// Cover the first of the `left` cells by each shape in turn, collecting up to two
+/// complete `splits`. Shapes list their cells from the top left, row by row.
+fn split_cells(
+    left: &mut BTreeSet<(usize, usize)>,
+    shapes: &[Vec<(usize, usize)>],
+    pieces: &mut Vec<Vec<(usize, usize)>>,
+    splits: &mut Vec<Vec<Vec<(usize, usize)>>>,
+) {
+    let Some(&(y, x)) = left.first() else {
+        splits.push(pieces.clone());
+        return;
+    };
+    for shape in shapes {
+        if splits.len() > 1 {
+            return;
+        }
+        let (first_x, first_y) = shape[0];
+        let piece: Option<Vec<(usize, usize)>> = shape
+            .iter()
+            .map(|&(sx, sy)| {
+                let cell = ((x + sx).checked_sub(first_x)?, y + sy - first_y);
+                left.contains(&(cell.1, cell.0)).then_some(cell)
+            })
+            .collect();
+        let Some(piece) = piece else {
+            continue;
+        };
+        piece.iter().for_each(|&(px, py)| {
+            left.remove(&(py, px));
+        });
+        pieces.push(piece);
+        split_cells(left, shapes, pieces, splits);
+        for (px, py) in pieces.pop().expect("the piece just pushed") {
+            left.insert((py, px));
+        }
     }
 }
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
+fn label_groups(rows: &[Vec<char>], label: char) -> Vec<Vec<(usize, usize)>> {
+    let mut seen: HashSet<(usize, usize)> = HashSet::new();
+    let mut groups = vec![];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, &c) in row.iter().enumerate() {
+            if c != label || seen.contains(&(x, y)) {
+                continue;
+            }
+            let mut group = vec![];
+            let mut stack = vec![(x, y)];
+            seen.insert((x, y));
+            while let Some((cx, cy)) = stack.pop() {
+                group.push((cx, cy));
+                let neighbors = [
+                    (cx.wrapping_sub(1), cy),
+                    (cx + 1, cy),
+                    (cx, cy.wrapping_sub(1)),
+                    (cx, cy + 1),
+                ];
+                for (nx, ny) in neighbors {
+                    let is_label = rows.get(ny).and_then(|r| r.get(nx)) == Some(&label);
+                    if is_label && seen.insert((nx, ny)) {
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+            groups.push(group);
+        }
+    }
+    groups
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
 pub struct Mino {
     pub name: char,
     pub shape: Shape,
+    #[serde(default)]
+    pub constraints: Constraints,
+    /// Colours of the cells, aligned with `shape`.
+    #[serde(default)]
+    pub colors: Option<Colors>,
+    /// Colours on the back of the cells, aligned with `shape` like `colors`.
+    #[serde(default)]
+    pub back_colors: Option<Colors>,
+    /// Full name from a version 2 piece file, which may be longer than `name`.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Colour the mino is printed in, like `red`, read by [`piece_format::parse_color`].
+    #[serde(default)]
+    pub display_color: Option<String>,
 }
 
 impl Mino {
     pub fn new(name: char, shape: Shape) -> Self {
-        Self { name, shape }
+        Self {
+            name,
+            shape,
+            constraints: Constraints::default(),
+            colors: None,
+            back_colors: None,
+            label: None,
+            display_color: None,
+        }
     }
     pub fn minos_from_path<P>(path: P) -> Result<Vec<Self>, String>
     where
         P: AsRef<Path>,
     {
-        if path.as_ref().is_file() {
-            Ok(Self::minos_from_text_path(path)?)
+        Self::minos_from_supplies(Self::supplies_from_path(path)?)
+    }
+    /// Like [`Mino::minos_from_path`], keeping each block's count as a [`Supply`]
+    /// so that ranges and unlimited counts are allowed.
+    pub fn supplies_from_path<P>(path: P) -> Result<Vec<(Self, Supply)>, String>
+    where
+        P: AsRef<Path>,
+    {
+        if path.as_ref().is_file() && json::is_json(&path) {
+            Self::supplies_from_json_path(path)
+        } else if path.as_ref().is_file() {
+            Ok(Self::supplies_from_text_path(path)?)
         } else if path.as_ref().is_dir() {
-            Ok(Self::minos_from_directory_path(path))
+            Self::supplies_from_directory(path, &DirectoryOptions::default())
         } else {
             Err(format!("Invalid path {:?}", path.as_ref()))
         }
     }
+    /// Repeat each mino by its count. Fails for counts which are not exact.
+    pub fn minos_from_supplies(supplies: Vec<(Self, Supply)>) -> Result<Vec<Self>, String> {
+        let mut minos = vec![];
+        for (mino, supply) in supplies {
+            let count = supply.exact_count().ok_or(format!(
+                "The count '{}' of mino '{}' is not an exact number",
+                supply, mino.name
+            ))?;
+            minos.extend(vec![mino; count]);
+        }
+        Ok(minos)
+    }
     pub fn pretty_print(&self) {
         info!("------------");
         self.shape.0.iter().for_each(|bools| {
@@ -318,52 +1032,59 @@ impl Mino {
         self.shape.width()
     }
     fn rotated(&self, rotation: &Rotation) -> Self {
-        let new_raw_shape = match rotation {
-            Rotation::Neutral => self.shape.clone(),
-            Rotation::Left => {
-                let mut right_shape = vec![vec![false; self.height()]; self.width()];
-                for y in 0..self.height() {
-                    for x in 0..self.width() {
-                        right_shape[self.width() - x - 1][y] = self.shape.is_wall(x, y);
-                    }
-                }
-                Shape(right_shape)
-            }
-            Rotation::Right => {
-                let mut left_shape = vec![vec![false; self.height()]; self.width()];
-                for y in 0..self.height() {
-                    (0..self.width()).for_each(|x| {
-                        left_shape[x][self.height() - y - 1] = self.shape.is_wall(x, y);
-                    });
-                }
-                Shape(left_shape)
-            }
-            Rotation::OneEighty => {
-                let mut one_eighty_shape = vec![vec![false; self.width()]; self.height()];
-                for y in 0..self.height() {
-                    for x in 0..self.width() {
-                        one_eighty_shape[self.height() - y - 1][self.width() - x - 1] =
-                            self.shape.is_wall(x, y);
-                    }
-                }
-                Shape(one_eighty_shape)
-            }
+        Self {
+            shape: Shape(rotate_grid(&self.shape.0, rotation)),
+            colors: self.colors.as_ref().map(|c| c.rotated(rotation)),
+            back_colors: self.back_colors.as_ref().map(|c| c.rotated(rotation)),
+            ..self.clone()
+        }
+    }
+    /// Mirrored left to right. A mino with a back face is turned over,
+    /// so the back colours are shown and the front colours are hidden.
+    fn flipped(&self) -> Self {
+        let (colors, back_colors) = match &self.back_colors {
+            Some(back) => (Some(back), self.colors.as_ref()),
+            None => (self.colors.as_ref(), None),
         };
         Self {
-            shape: new_raw_shape,
-            name: self.name,
+            shape: Shape(flip_grid(&self.shape.0)),
+            colors: colors.map(|c| c.flipped()),
+            back_colors: back_colors.map(|c| c.flipped()),
+            ..self.clone()
         }
     }
-    fn minos_from_directory_path<P>(directory_path: P) -> Vec<Self>
-    where
-        P: AsRef<Path>,
-    {
-        directory_path
-            .as_ref()
-            .read_dir()
-            .unwrap()
-            .flat_map(|entry| Self::minos_from_text_path(entry.unwrap().path()).unwrap())
-            .collect()
+    /// Colours which are only given for cells of the mino.
+    fn cell_colors(&self, s: &str) -> Result<Colors, String> {
+        let colors = Colors::from_str(s)?;
+        let fits = (0..colors.height()).all(|y| {
+            (0..colors.width()).all(|x| {
+                colors.get(x, y).is_none()
+                    || (x < self.width() && y < self.height() && self.shape.is_wall(x, y))
+            })
+        });
+        if !fits {
+            return Err(format!(
+                "Colors of mino '{}' are outside its cells",
+                self.name
+            ));
+        }
+        Ok(colors)
+    }
+    /// Orientations the mino may be put in. Minos with a back face may be flipped
+    /// unless their constraints say otherwise.
+    pub fn orientations(&self) -> Vec<Orientation> {
+        match (&self.constraints.orientations, &self.back_colors) {
+            (Some(orientations), _) => orientations.clone(),
+            (None, Some(_)) => Orientation::all(),
+            (None, None) => Orientation::rotations(),
+        }
+    }
+    fn oriented(&self, orientation: &Orientation) -> Self {
+        if orientation.flipped {
+            self.flipped().rotated(&orientation.rotation)
+        } else {
+            self.rotated(&orientation.rotation)
+        }
     }
     /// Read minos from a text file.
     /// The format of the text file is as follows:
@@ -372,12 +1093,18 @@ impl Mino {
     where
         P: AsRef<Path>,
     {
-        let lines: Vec<String> = fs::read_to_string(p)
+        Self::minos_from_supplies(Self::supplies_from_text_path(p)?)
+    }
+    pub fn supplies_from_text_path<P>(p: P) -> Result<Vec<(Self, Supply)>, String>
+    where
+        P: AsRef<Path>,
+    {
+        let lines: Vec<String> = fs::read_to_string(&p)
             .map_err(|e| e.to_string())?
             .lines()
             .map(|s| s.to_string())
             .collect();
-        Self::minos_from_lines(lines)
+        Self::supplies_from_lines(lines).map_err(|e| format!("{}: {}", p.as_ref().display(), e))
     }
     /// ```
     /// let lines = vec![
@@ -394,32 +1121,129 @@ impl Mino {
     /// let minos = tiling_mino_solver::Mino::minos_from_lines(lines.iter().map(|s| s.to_string()).collect()).unwrap();
     /// ```
     pub fn minos_from_lines(lines: Vec<String>) -> Result<Vec<Mino>, String> {
+        Self::minos_from_supplies(Self::supplies_from_lines(lines)?)
+    }
+    /// The first line of each block is its [`Supply`], e.g. `*` for any number of copies,
+    /// followed by optional `key: value` lines read by [`Constraints::read_line`].
+    ///
+    /// ```
+    /// use tiling_mino_solver::{Mino, cover::Supply};
+    /// let lines = vec!["*", "a.", "aa", "------------", "1..2", "bbb"];
+    /// let supplies = Mino::supplies_from_lines(lines.iter().map(|s| s.to_string()).collect()).unwrap();
+    /// assert_eq!(supplies[0].1, Supply::unlimited());
+    /// assert_eq!(supplies[1].1, Supply { min: 1, max: Some(2) });
+    /// ```
+    pub fn supplies_from_lines(lines: Vec<String>) -> Result<Vec<(Mino, Supply)>, String> {
+        let supplies = Self::read_supplies(lines)?;
+        symmetry::warn_duplicate_shapes(&supplies);
+        Ok(supplies)
+    }
+    fn read_supplies(lines: Vec<String>) -> Result<Vec<(Mino, Supply)>, String> {
+        if piece_format::is_version_2(&lines) {
+            return piece_format::read_supplies(&lines);
+        }
+        let mut start = 1;
         lines
             .split(|line| line.contains('-'))
-            .flat_map(|block| {
-                let count = block[0].parse::<usize>().unwrap();
-                let s: String = block[1..].join("\n");
-                vec![Mino::from_str(&s); count]
+            .map(|block| {
+                let line = start;
+                start += block.len() + 1;
+                Self::read_block(block).map_err(|e| format!("Block at line {}: {}", line, e))
             })
             .collect()
     }
+    fn read_block(block: &[String]) -> Result<(Mino, Supply), String> {
+        let supply = Supply::from_str(block.first().ok_or("The block is empty")?)?;
+        let n_constraints = block[1..]
+            .iter()
+            .take_while(|line| line.contains(':'))
+            .count();
+        let mut constraints = Constraints::default();
+        for line in &block[1..=n_constraints] {
+            constraints.read_line(line)?;
+        }
+        let s: String = block[1 + n_constraints..].join("\n");
+        let mut mino = Mino::from_str(&s)?;
+        mino.constraints = constraints;
+        Ok((mino, supply))
+    }
+    /// Inverse of [`Mino::minos_from_lines`]. Consecutive equal minos share a block.
+    pub fn minos_to_lines(minos: &[Mino]) -> Vec<String> {
+        let supplies: Vec<(Mino, Supply)> = minos
+            .chunk_by(|a, b| a == b)
+            .map(|chunk| (chunk[0].clone(), Supply::exactly(chunk.len())))
+            .collect();
+        Self::supplies_to_lines(&supplies)
+    }
+    /// Inverse of [`Mino::supplies_from_lines`].
+    pub fn supplies_to_lines(supplies: &[(Mino, Supply)]) -> Vec<String> {
+        supplies
+            .iter()
+            .map(|(mino, supply)| {
+                let mut block = vec![supply.to_string()];
+                block.extend(mino.constraints.to_lines());
+                block.extend(mino.to_lines());
+                block
+            })
+            .collect::<Vec<Vec<String>>>()
+            .join(&"-".repeat(12))
+    }
+    /// Inverse of [`Mino::from_str`].
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .shape
+            .0
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&b| if b { self.name } else { '.' })
+                    .collect()
+            })
+            .collect();
+        if self.colors.is_some() || self.back_colors.is_some() {
+            let blank = Colors::new(vec![vec![None; self.width()]; self.height()]);
+            lines.push("=".to_string());
+            lines.extend(self.colors.as_ref().unwrap_or(&blank).to_lines());
+            if let Some(back) = &self.back_colors {
+                lines.push("=".to_string());
+                lines.extend(back.to_lines());
+            }
+        }
+        lines
+    }
 }
 
 impl FromStr for Mino {
     type Err = String;
+    /// Rows of the mino, optionally followed by a line of '=' and the colours of its cells.
+    /// Another line of '=' and colours gives the back face, aligned with the front cells.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (s, colors) = split_colors(s);
+        let s = s.as_str();
         let mut cs: HashSet<char> = s.trim().chars().collect();
         cs.remove(&'.');
         cs.remove(&'\n');
         if cs.len() != 1 {
-            info!("{}", s);
+            let mut names: Vec<char> = cs.into_iter().collect();
+            names.sort();
+            return Err(format!(
+                "A mino needs exactly one name character, found {:?}",
+                names
+            ));
         }
-        assert_eq!(cs.len(), 1);
         let name = cs.into_iter().collect::<Vec<char>>()[0];
-        Ok(Self {
-            name,
-            shape: Shape::from_str(s)?,
-        })
+        let mut mino = Self::new(name, Shape::from_str(s)?);
+        if mino.shape.components(geometry::Connectivity::Four).len() != 1 {
+            return Err(format!("Mino '{}' is not connected", name));
+        }
+        if let Some(colors) = colors {
+            let (front, back) = split_colors(&colors);
+            mino.colors = Some(mino.cell_colors(&front)?);
+            if let Some(back) = back {
+                mino.back_colors = Some(mino.cell_colors(&back)?);
+            }
+        }
+        Ok(mino)
     }
 }
 
@@ -446,6 +1270,29 @@ pub fn check_wall_count(minos: &Vec<Mino>, board: &Board) {
     );
 }
 
+/// Like [`check_wall_count`] for [`Board::tile_subset`], where minos are optional:
+/// the minos only need to be able to cover the vacant cells. The error explains
+/// why they can not, in the words of [`Board::infeasibility`].
+///
+/// ```
+/// use std::str::FromStr;
+/// use tiling_mino_solver::{Board, Mino, check_subset_wall_count};
+/// let board = Board::from_str("...").unwrap();
+/// let minos = vec![Mino::from_str("aa").unwrap()];
+/// assert!(check_subset_wall_count(&minos, &board).unwrap_err().starts_with("Area"));
+/// ```
+pub fn check_subset_wall_count(minos: &[Mino], board: &Board) -> Result<(), String> {
+    let count_mino_walls: usize = minos.iter().map(|mino| mino.shape.count_wall()).sum();
+    if count_mino_walls < board.shape.count_vacant() {
+        return Err(format!(
+            "Area: the minos cover at most {} cells but the board has {} vacant cells",
+            count_mino_walls,
+            board.shape.count_vacant()
+        ));
+    }
+    Ok(())
+}
+
 #[test]
 fn test_mino_from_str() {
     let mino = Mino::from_str("###\n.##").unwrap();
@@ -498,6 +1345,25 @@ fn test_mino_rotated_one_eighty() {
     );
 }
 
+#[test]
+fn test_mino_oriented_flipped() {
+    // ###
+    // ..#
+    let mino = Mino::from_str("###\n..#").unwrap();
+    // ###
+    // #..
+    let flipped = Orientation::from_str("flipped_neutral").unwrap();
+    assert_eq!(mino.oriented(&flipped), Mino::from_str("###\n#..").unwrap());
+    // #.
+    // #.
+    // ##
+    let flipped_left = Orientation::from_str("flipped_left").unwrap();
+    assert_eq!(
+        mino.oriented(&flipped_left),
+        Mino::from_str("#.\n#.\n##").unwrap()
+    );
+}
+
 #[test]
 fn test_put_mino() {
     let mut board = Board::from_text_path("testdata/board.txt").unwrap();
@@ -512,6 +1378,7 @@ aa",
         x: 1,
         y: 1,
         rotation: Rotation::Neutral,
+        flipped: false,
     };
     assert!(board.can_put(&mino, &t));
     let expected = "####...
@@ -539,6 +1406,7 @@ aa",
         x: 1,
         y: 1,
         rotation: Rotation::Right,
+        flipped: false,
     };
     assert!(board.can_put(&mino, &t));
     let expected = "####...
@@ -563,6 +1431,135 @@ fn test_board_from_str_when_invalid() {
     );
 }
 
+#[test]
+fn test_board_from_str_with_minos() {
+    let minos = Mino::minos_from_text_path("testdata/minos.txt").unwrap();
+    let s = "####...
+#a.....
+.aa....
+.aa...#
+.aa...#
+......#
+#...###";
+    let board = Board::from_str_with_minos(s, &minos).unwrap();
+    assert_eq!(board.pretty_shape(), s);
+    assert_eq!(
+        board.fixed_mino_transforms(),
+        &[(
+            minos[0].clone(),
+            TransForm {
+                x: 1,
+                y: 1,
+                rotation: Rotation::Neutral,
+                flipped: false,
+            }
+        )]
+    );
+    let unplaced = board.unplaced_minos(&minos).unwrap();
+    assert_eq!(unplaced, minos[1..].to_vec());
+    check_wall_count(&unplaced, &board);
+}
+
+#[test]
+fn test_board_from_str_with_rotated_minos() {
+    let minos = vec![Mino::from_str("a.\naa\naa\naa").unwrap(); 2];
+    let board = Board::from_str_with_minos("aaaa.\naaa..\n.....\n.aaa.\naaaa.", &minos).unwrap();
+    let rotations: Vec<Rotation> = board
+        .fixed_mino_transforms()
+        .iter()
+        .map(|(_, t)| t.rotation.clone())
+        .collect();
+    assert_eq!(rotations, vec![Rotation::Right, Rotation::Left]);
+    assert!(board.unplaced_minos(&minos).unwrap().is_empty());
+}
+
+#[test]
+fn test_remove_fixed_mino_and_undo() {
+    let minos = vec![
+        Mino::from_str("a.\naa").unwrap(),
+        Mino::from_str("bbb").unwrap(),
+    ];
+    let original = Board::from_str_with_minos("aa.\na..", &minos).unwrap();
+    let mut board = original.clone();
+    assert_eq!(board.remove_mino(0).0, minos[0]);
+    assert!(board.fixed_mino_transforms().is_empty());
+    assert_eq!(board.shape.count_vacant(), 6);
+    assert!(board.undo());
+    assert_eq!(board, original);
+    assert_eq!(board.fixed_mino_transforms().len(), 1);
+    assert!(board.redo());
+    assert!(!board.redo());
+    assert!(board.fixed_mino_transforms().is_empty());
+}
+
+#[test]
+fn test_tile_in_place_restores_board_when_failing() {
+    let board = Board::from_str("...\n...").unwrap();
+    let minos = vec![
+        Mino::from_str("aa\naa").unwrap(),
+        Mino::from_str("bbb").unwrap(),
+    ];
+    let mut searched = board.clone();
+    assert!(!searched.tile_in_place(&minos));
+    assert_eq!(searched, board);
+    assert!(searched.tile_in_place(&minos[1..]));
+    assert_eq!(searched.mino_transforms().len(), 1);
+}
+
+#[test]
+fn test_board_from_str_with_touching_copies() {
+    let bar = vec![Mino::from_str("aa").unwrap(); 2];
+    let board = Board::from_str_with_minos("aaaa\n....", &bar).unwrap();
+    assert_eq!(board.fixed_mino_transforms().len(), 2);
+    assert_eq!(board.unplaced_minos(&bar).unwrap(), vec![]);
+    // Only one way puts two L trominoes on these cells.
+    let l = vec![Mino::from_str("a.\naa").unwrap(); 2];
+    let board = Board::from_str_with_minos("aa.\naaa\n.a.", &l).unwrap();
+    let mut pieces: Vec<Vec<(usize, usize)>> = board
+        .fixed_mino_transforms()
+        .iter()
+        .map(|(mino, t)| {
+            board
+                .covered_cells(&mino.oriented(&t.orientation()), t)
+                .unwrap()
+        })
+        .collect();
+    pieces.sort();
+    assert_eq!(
+        pieces,
+        vec![vec![(0, 0), (1, 0), (0, 1)], vec![(1, 1), (2, 1), (1, 2)]]
+    );
+}
+
+#[test]
+fn test_board_from_str_with_minos_when_invalid() {
+    let minos = vec![Mino::from_str("a.\naa").unwrap()];
+    assert_eq!(
+        Board::from_str_with_minos("z..\n...", &minos).unwrap_err(),
+        "No mino named 'z' for the board"
+    );
+    assert_eq!(
+        Board::from_str_with_minos(".aaa\n....", &minos).unwrap_err(),
+        "Cells of 'a' at (1, 0) do not match the shape of the mino"
+    );
+    let bar = vec![Mino::from_str("aa").unwrap(); 2];
+    assert!(Board::from_str_with_minos("aa.\n...", &bar).is_ok());
+    assert_eq!(
+        Board::from_str_with_minos("aa.\naa.", &bar).unwrap_err(),
+        "Cells of 'a' at (0, 0) can be split into copies of the mino in more \
+        than one way; use a JSON board to fix the copies"
+    );
+    assert_eq!(
+        Board::from_str_with_minos("aaa\n...", &bar).unwrap_err(),
+        "Cells of 'a' at (0, 0) do not match the shape of the mino"
+    );
+    let board = Board::from_str_with_minos("a..a.\naa.aa", &minos).unwrap();
+    assert_eq!(
+        board.unplaced_minos(&minos).unwrap_err(),
+        "Mino 'a' is on the board too many times"
+    );
+}
+
 #[test]
 fn test_char_matrix() {
     let s = "###.\n#.#.";
@@ -623,7 +1620,42 @@ fn test_minos_from_text_path() {
     assert_eq!(minos, expected);
 }
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Debug, PartialOrd, Ord)]
+/// Rotate a grid of rows like [`Rotation`] rotates a mino.
+pub(crate) fn rotate_grid<T: Clone>(grid: &[Vec<T>], rotation: &Rotation) -> Vec<Vec<T>> {
+    let height = grid.len();
+    let width = grid.first().map_or(0, |row| row.len());
+    match rotation {
+        Rotation::Neutral => grid.to_vec(),
+        Rotation::Left => (0..width)
+            .map(|r| {
+                (0..height)
+                    .map(|c| grid[c][width - 1 - r].clone())
+                    .collect()
+            })
+            .collect(),
+        Rotation::Right => (0..width)
+            .map(|r| {
+                (0..height)
+                    .map(|c| grid[height - 1 - c][r].clone())
+                    .collect()
+            })
+            .collect(),
+        Rotation::OneEighty => grid
+            .iter()
+            .rev()
+            .map(|row| row.iter().rev().cloned().collect())
+            .collect(),
+    }
+}
+
+/// Mirror a grid of rows left to right.
+pub(crate) fn flip_grid<T: Clone>(grid: &[Vec<T>]) -> Vec<Vec<T>> {
+    grid.iter()
+        .map(|row| row.iter().rev().cloned().collect())
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
 pub struct Shape(Vec<Vec<bool>>);
 
 impl Shape {
@@ -761,21 +1793,21 @@ fn bench_tile_serial() {
 #[test]
 fn test_shape_toggle() {
     let mut shape = Shape::from_str("##\n.#").unwrap();
-    assert_eq!(shape.is_wall(0, 0), true);
-    assert_eq!(shape.is_wall(1, 0), true);
-    assert_eq!(shape.is_wall(0, 1), false);
-    assert_eq!(shape.is_wall(1, 1), true);
+    assert!(shape.is_wall(0, 0));
+    assert!(shape.is_wall(1, 0));
+    assert!(!shape.is_wall(0, 1));
+    assert!(shape.is_wall(1, 1));
 
     shape.toggle(0, 1);
-    assert_eq!(shape.is_wall(0, 0), true);
-    assert_eq!(shape.is_wall(1, 0), true);
-    assert_eq!(shape.is_wall(0, 1), true);
-    assert_eq!(shape.is_wall(1, 1), true);
+    assert!(shape.is_wall(0, 0));
+    assert!(shape.is_wall(1, 0));
+    assert!(shape.is_wall(0, 1));
+    assert!(shape.is_wall(1, 1));
     shape.toggle(0, 0);
-    assert_eq!(shape.is_wall(0, 0), false);
-    assert_eq!(shape.is_wall(1, 0), true);
-    assert_eq!(shape.is_wall(0, 1), true);
-    assert_eq!(shape.is_wall(1, 1), true);
+    assert!(!shape.is_wall(0, 0));
+    assert!(shape.is_wall(1, 0));
+    assert!(shape.is_wall(0, 1));
+    assert!(shape.is_wall(1, 1));
 }
 
 #[test]
@@ -1,6 +1,20 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
 use rayon::ThreadPoolBuilder;
 use std::env;
-use tiling_mino_solver::{Board, Mino, check_wall_count};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::AtomicUsize;
+use std::time::Duration;
+use tiling_mino_solver::batch::{read_batch, write_batch};
+use tiling_mino_solver::catalog::catalog;
+use tiling_mino_solver::cover::Supply;
+use tiling_mino_solver::editor::{Edit, Editor};
+use tiling_mino_solver::packing::PackObjective;
+use tiling_mino_solver::play::{Action, Play};
+use tiling_mino_solver::polyomino::{Equivalence, generate_minos};
+use tiling_mino_solver::puzzle::{Mode, Puzzle, Report};
+use tiling_mino_solver::{Board, Mino};
 
 pub const NUM_THREADS: usize = 8;
 
@@ -10,32 +24,428 @@ fn main() {
         .with_thread_names(true)
         .with_file(true)
         .with_line_number(true)
+        .with_writer(io::stderr)
         .init();
     ThreadPoolBuilder::new()
         .num_threads(NUM_THREADS)
         .build_global()
         .unwrap();
     let args: Vec<String> = env::args().collect();
-    let (minos_path, board_path) = if args.len() == 3 {
-        (args[1].clone(), args[2].clone())
+    if args.get(1).is_some_and(|arg| arg == "generate") {
+        if let Err(e) = generate(&args[2..]) {
+            tracing::error!("{}", e);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|arg| arg == "play") {
+        if let Err(e) = play(&args[2..]) {
+            tracing::error!("{}", e);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|arg| arg == "edit") {
+        if let Err(e) = edit(&args[2..]) {
+            tracing::error!("{}", e);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|arg| arg == "batch") {
+        if let Err(e) = batch(&args[2..]) {
+            tracing::error!("{}", e);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|arg| arg == "pack") {
+        if let Err(e) = pack(&args[2..]) {
+            tracing::error!("{}", e);
+        }
+        return;
+    }
+    if args.get(1).is_some_and(|arg| arg == "analyze") {
+        if let Err(e) = analyze(&args[2..]) {
+            tracing::error!("{}", e);
+        }
+        return;
+    }
+    let mode = if args.iter().any(|arg| arg == "--subset") {
+        Some(Mode::Subset)
+    } else if args.iter().any(|arg| arg == "--supply") {
+        Some(Mode::Supply)
     } else {
-        ("data/minos".to_string(), "data/board.txt".to_string())
+        None
+    };
+    let diagnose = args.iter().any(|arg| arg == "--diagnose");
+    let json = args.iter().any(|arg| arg == "--json");
+    let pieces = args
+        .iter()
+        .position(|arg| arg == "--pieces")
+        .and_then(|i| args.get(i + 1).cloned());
+    let args: Vec<String> = args
+        .iter()
+        .enumerate()
+        .filter(|&(i, arg)| !arg.starts_with("--") && (i == 0 || args[i - 1] != "--pieces"))
+        .map(|(_, arg)| arg.clone())
+        .collect();
+    let puzzle = match pieces {
+        Some(name) => load(
+            Pieces::Catalog(name),
+            args.get(1).cloned().unwrap_or("data/board.txt".to_string()),
+            mode,
+        ),
+        None if args.len() == 2 => {
+            let puzzle = if args[1] == "-" {
+                read_stdin().and_then(|s| Puzzle::from_json_str(&s, Path::new("")))
+            } else {
+                Puzzle::from_path(&args[1])
+            };
+            puzzle.map(|mut puzzle| {
+                puzzle.mode = mode.unwrap_or(puzzle.mode);
+                puzzle
+            })
+        }
+        None if args.len() == 3 => load(Pieces::Path(args[1].clone()), args[2].clone(), mode),
+        None => load(
+            Pieces::Path("data/minos".to_string()),
+            "data/board.txt".to_string(),
+            mode,
+        ),
     };
-    let _ = solve(minos_path, board_path);
+    if let Err(e) = puzzle.and_then(|puzzle| solve(&puzzle, diagnose, json)) {
+        tracing::error!("{}", e);
+    }
     tracing::info!("Tiling Mino Solver finished");
 }
 
-pub fn solve(minos_path: String, board_path: String) -> Result<(), String> {
-    let mut minos: Vec<Mino> = Mino::minos_from_path(minos_path)?;
-    minos.sort_by_key(|m| m.count_wall());
-    minos.reverse();
-    let board = Board::from_text_path(board_path)?;
-    check_wall_count(&minos, &board);
-    let tiled = board.tile_parallel(&minos);
-    if let Some(board) = tiled {
-        board.pretty_print();
+/// All of stdin, for paths given as "-".
+fn read_stdin() -> Result<String, String> {
+    io::read_to_string(io::stdin()).map_err(|e| format!("stdin: {}", e))
+}
+
+/// Where the minos come from.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Pieces {
+    /// A minos text file or directory, or "-" for minos text on stdin.
+    Path(String),
+    /// A built-in piece set (`--pieces <name>`).
+    Catalog(String),
+}
+
+impl Pieces {
+    fn supplies(&self) -> Result<Vec<(Mino, Supply)>, String> {
+        match self {
+            Pieces::Path(path) if path == "-" => {
+                Mino::supplies_from_lines(read_stdin()?.lines().map(String::from).collect())
+            }
+            Pieces::Path(path) => Mino::supplies_from_path(path),
+            Pieces::Catalog(name) => Ok(catalog(name)?
+                .into_iter()
+                .map(|mino| (mino, Supply::exactly(1)))
+                .collect()),
+        }
+    }
+}
+
+/// A puzzle from separate minos and board paths, in `mode` or [`Mode::Exact`].
+/// One of the paths may be "-" for stdin.
+fn load(pieces: Pieces, board_path: String, mode: Option<Mode>) -> Result<Puzzle, String> {
+    if board_path == "-" && pieces == Pieces::Path("-".to_string()) {
+        return Err("Only one of the minos and board paths can be '-'".to_string());
+    }
+    let supplies = pieces.supplies()?;
+    let kinds: Vec<Mino> = supplies.iter().map(|(m, _)| m.clone()).collect();
+    let board = if board_path == "-" {
+        Board::from_str_with_minos(&read_stdin()?, &kinds)?
+    } else {
+        Board::from_path_with_minos(board_path, &kinds)?
+    };
+    board.unplaced_supplies(&supplies)?;
+    Ok(Puzzle::new(board, supplies, mode.unwrap_or_default()))
+}
+
+/// With `diagnose` (`--diagnose`), a failed exact tiling is followed by
+/// [`Board::diagnose`] to find which minos or cells make it impossible.
+/// With `json` (`--json`), the result is written to stdout as one line of a [`Report`],
+/// and nothing else is, so the search does not print its progress.
+pub fn solve(puzzle: &Puzzle, diagnose: bool, json: bool) -> Result<(), String> {
+    let tiled = if json {
+        puzzle.solve_counting(&AtomicUsize::new(0))
     } else {
-        tracing::info!("Can NOT resolved");
+        puzzle.solve()
+    };
+    if tiled.is_err()
+        && diagnose
+        && puzzle.mode == Mode::Exact
+        && let Ok(minos) = puzzle.unplaced_minos()
+    {
+        let board = &puzzle.board;
+        for line in board.diagnose(&minos).to_lines(board) {
+            tracing::info!("{}", line);
+        }
+    }
+    if json {
+        let report = serde_json::to_string(&Report::new(&tiled)).map_err(|e| e.to_string())?;
+        println!("{}", report);
+        return Ok(());
+    }
+    match tiled {
+        Ok(board) => {
+            board.pretty_print();
+            if puzzle.mode != Mode::Exact {
+                let used: String = board
+                    .mino_transforms()
+                    .iter()
+                    .map(|(m, _)| m.name)
+                    .collect();
+                tracing::info!("Used minos: {}", used);
+            }
+        }
+        Err(reason) => tracing::info!("Can NOT resolved: {}", reason),
+    }
+    Ok(())
+}
+
+/// Print every polyomino of a size in the minos text format:
+/// `generate <n> [free|one-sided|fixed]`.
+pub fn generate(args: &[String]) -> Result<(), String> {
+    let n = args
+        .first()
+        .ok_or("Usage: generate <n> [free|one-sided|fixed]")?
+        .parse::<usize>()
+        .map_err(|e| e.to_string())?;
+    let equivalence = match args.get(1) {
+        Some(s) => s.parse::<Equivalence>()?,
+        None => Equivalence::Free,
+    };
+    for line in Mino::minos_to_lines(&generate_minos(n, equivalence)?) {
+        println!("{}", line);
     }
     Ok(())
 }
+
+/// Put as many of the minos on the board as possible, leaving the other cells vacant:
+/// `pack <minos_path> <board_path> [area|pieces]`, maximising the covered area by default.
+pub fn pack(args: &[String]) -> Result<(), String> {
+    let [minos_path, board_path, rest @ ..] = args else {
+        return Err("Usage: pack <minos_path> <board_path> [area|pieces]".to_string());
+    };
+    let objective = match rest.first() {
+        Some(s) => s.parse::<PackObjective>()?,
+        None => PackObjective::Area,
+    };
+    let minos = Mino::minos_from_path(minos_path)?;
+    let board = Board::from_path_with_minos(board_path, &minos)?;
+    let packing = board.pack(&board.unplaced_minos(&minos)?, objective);
+    packing.board.pretty_print();
+    let unplaced: String = packing.unplaced.iter().map(|m| m.name).collect();
+    tracing::info!("Score: {}, unplaced minos: {}", packing.score, unplaced);
+    Ok(())
+}
+
+/// Print the geometry of each mino of a minos file, or of the vacant cells of a board:
+/// `analyze minos <path>` or `analyze board <path>`.
+pub fn analyze(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: analyze minos|board <path>";
+    let path = args.get(1).ok_or(usage)?;
+    match args[0].as_str() {
+        "minos" => {
+            for (mino, _) in Mino::supplies_from_path(path)? {
+                println!("{}", mino.name);
+                for line in mino.shape.analysis_lines() {
+                    println!("  {}", line);
+                }
+            }
+        }
+        "board" => {
+            let board = Board::from_path(path)?;
+            for line in board.shape.inverted().analysis_lines() {
+                println!("{}", line);
+            }
+        }
+        _ => return Err(usage.to_string()),
+    }
+    Ok(())
+}
+
+/// Solve a directory of puzzle files or a JSONL file of puzzles in parallel:
+/// `batch <path>`. Each result is printed as one line of JSON as soon as it is found,
+/// so the lines are not in the order of the puzzles.
+pub fn batch(args: &[String]) -> Result<(), String> {
+    let path = args
+        .first()
+        .ok_or("Usage: batch <directory|puzzles.jsonl>")?;
+    let entries = read_batch(path)?;
+    let solved = write_batch(&entries, io::stdout())?;
+    tracing::info!("Solved {} of {} puzzles", solved, entries.len());
+    Ok(())
+}
+
+/// Play a puzzle in the terminal: `play [<minos_path> <board_path>]`.
+pub fn play(args: &[String]) -> Result<(), String> {
+    let (minos_path, board_path) = match args {
+        [minos_path, board_path, ..] => (minos_path.as_str(), board_path.as_str()),
+        _ => ("data/minos", "data/board.txt"),
+    };
+    let minos = Mino::minos_from_path(minos_path)?;
+    let board = Board::from_path_with_minos(board_path, &minos)?;
+    let remaining = board.unplaced_minos(&minos)?;
+    let mut play = Play::new(board, remaining);
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide).map_err(|e| e.to_string())?;
+    let result = play_loop(&mut play, &mut stdout);
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen).map_err(|e| e.to_string())?;
+    terminal::disable_raw_mode().map_err(|e| e.to_string())?;
+    result
+}
+
+fn play_loop(play: &mut Play, stdout: &mut io::Stdout) -> Result<(), String> {
+    let help = "arrows: move  tab: next mino  r: rotate  f: flip  space: place  \
+        x: remove  u: undo  y: redo  h: hint  q: quit";
+    let mut redraw = true;
+    loop {
+        if redraw {
+            execute!(
+                stdout,
+                terminal::Clear(terminal::ClearType::All),
+                cursor::MoveTo(0, 0)
+            )
+            .map_err(|e| e.to_string())?;
+            for line in play.lines() {
+                write!(stdout, "{}\r\n", line).map_err(|e| e.to_string())?;
+            }
+            write!(stdout, "{}\r\n", help).map_err(|e| e.to_string())?;
+            stdout.flush().map_err(|e| e.to_string())?;
+        }
+        redraw = true;
+        // Wait for a key, drawing the status again once the check of the position is done.
+        if !event::poll(Duration::from_millis(100)).map_err(|e| e.to_string())? {
+            redraw = play.poll();
+            continue;
+        }
+        let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let action = match key.code {
+            KeyCode::Left => Action::Move(-1, 0),
+            KeyCode::Right => Action::Move(1, 0),
+            KeyCode::Up => Action::Move(0, -1),
+            KeyCode::Down => Action::Move(0, 1),
+            KeyCode::Tab => Action::Next,
+            KeyCode::BackTab => Action::Previous,
+            KeyCode::Char('r') => Action::Rotate,
+            KeyCode::Char('f') => Action::Flip,
+            KeyCode::Char(' ') | KeyCode::Enter => Action::Place,
+            KeyCode::Char('x') | KeyCode::Backspace => Action::Remove,
+            KeyCode::Char('u') => Action::Undo,
+            KeyCode::Char('y') => Action::Redo,
+            KeyCode::Char('h') => Action::Hint,
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            _ => continue,
+        };
+        play.apply(action);
+    }
+}
+
+/// Edit a board or a mino in the terminal:
+/// `edit board <path> [<minos_path>]` or `edit mino <path> [<name>]`.
+/// Minos from `minos_path` label the board and are used to preview a solution.
+pub fn edit(args: &[String]) -> Result<(), String> {
+    let usage = "Usage: edit board <path> [<minos_path>] | edit mino <path> [<name>]";
+    let (path, minos, mut editor) = match args {
+        [kind, path] if kind == "mino" => (path, vec![], Editor::open_mino(path, None)?),
+        [kind, path, name] if kind == "mino" => {
+            let mut chars = name.chars();
+            let (Some(name), None) = (chars.next(), chars.next()) else {
+                return Err(usage.to_string());
+            };
+            (path, vec![], Editor::open_mino(path, Some(name))?)
+        }
+        [kind, path] if kind == "board" => (
+            path,
+            Mino::minos_from_path("data/minos")?,
+            Editor::open(path, true)?,
+        ),
+        [kind, path, minos_path] if kind == "board" => (
+            path,
+            Mino::minos_from_path(minos_path)?,
+            Editor::open(path, true)?,
+        ),
+        _ => return Err(usage.to_string()),
+    };
+    let mut stdout = io::stdout();
+    terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide).map_err(|e| e.to_string())?;
+    let result = edit_loop(&mut editor, path, &minos, &mut stdout);
+    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen).map_err(|e| e.to_string())?;
+    terminal::disable_raw_mode().map_err(|e| e.to_string())?;
+    result
+}
+
+fn edit_loop(
+    editor: &mut Editor,
+    path: &str,
+    minos: &[Mino],
+    stdout: &mut io::Stdout,
+) -> Result<(), String> {
+    let help = "arrows: move  space: toggle  letter: paint  backspace: erase  \
+        ] [: width  } {: height  > <: column at cursor  + -: row at cursor  \
+        *: pad  /: trim  ctrl-s: save  ctrl-r: solve  esc: quit";
+    let mut saved = String::new();
+    loop {
+        execute!(
+            stdout,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )
+        .map_err(|e| e.to_string())?;
+        for line in editor.lines() {
+            write!(stdout, "{}\r\n", line).map_err(|e| e.to_string())?;
+        }
+        write!(stdout, "{}\r\n{}\r\n", saved, help).map_err(|e| e.to_string())?;
+        stdout.flush().map_err(|e| e.to_string())?;
+        let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        saved.clear();
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        let edit = match key.code {
+            KeyCode::Char('s') if control => {
+                saved = match editor.save(path, minos) {
+                    Ok(()) => format!("Saved to {}", path),
+                    Err(e) => e,
+                };
+                continue;
+            }
+            KeyCode::Char('r') if control => {
+                editor.solve_preview(minos);
+                continue;
+            }
+            KeyCode::Left => Edit::Move(-1, 0),
+            KeyCode::Right => Edit::Move(1, 0),
+            KeyCode::Up => Edit::Move(0, -1),
+            KeyCode::Down => Edit::Move(0, 1),
+            KeyCode::Char(' ') => Edit::Toggle,
+            KeyCode::Backspace | KeyCode::Delete => Edit::Erase,
+            KeyCode::Char(']') => Edit::Wider,
+            KeyCode::Char('[') => Edit::Narrower,
+            KeyCode::Char('}') => Edit::Taller,
+            KeyCode::Char('{') => Edit::Shorter,
+            KeyCode::Char('>') => Edit::InsertColumn,
+            KeyCode::Char('<') => Edit::RemoveColumn,
+            KeyCode::Char('+') => Edit::InsertRow,
+            KeyCode::Char('-') => Edit::RemoveRow,
+            KeyCode::Char('*') => Edit::Pad,
+            KeyCode::Char('/') => Edit::Trim,
+            KeyCode::Char(c) if c.is_alphanumeric() => Edit::Paint(c),
+            KeyCode::Esc => return Ok(()),
+            _ => continue,
+        };
+        editor.apply(edit);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::{Board, Mino};
+use std::str::FromStr;
+
+/// What a packing tries to maximise.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PackObjective {
+    /// Number of board cells covered by placed minos.
+    Area,
+    /// Number of placed minos.
+    Pieces,
+}
+
+impl FromStr for PackObjective {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "area" => Ok(PackObjective::Area),
+            "pieces" => Ok(PackObjective::Pieces),
+            _ => Err(format!("Unknown packing objective '{}'", s)),
+        }
+    }
+}
+
+impl PackObjective {
+    fn score(&self, mino: &Mino) -> usize {
+        match self {
+            PackObjective::Area => mino.count_wall(),
+            PackObjective::Pieces => 1,
+        }
+    }
+    /// Upper bound of the score reachable with `minos` on `n_vacant` cells.
+    fn bound(&self, minos: &[Mino], n_vacant: usize) -> usize {
+        match self {
+            PackObjective::Area => minos
+                .iter()
+                .map(|m| m.count_wall())
+                .sum::<usize>()
+                .min(n_vacant),
+            PackObjective::Pieces => {
+                let mut areas: Vec<usize> = minos.iter().map(|m| m.count_wall()).collect();
+                areas.sort();
+                let mut total = 0;
+                areas
+                    .into_iter()
+                    .take_while(|area| {
+                        total += area;
+                        total <= n_vacant
+                    })
+                    .count()
+            }
+        }
+    }
+}
+
+/// The best packing found by [`Board::pack`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Packing {
+    pub board: Board,
+    pub score: usize,
+    pub unplaced: Vec<Mino>,
+}
+
+struct PackSearch<'a> {
+    minos: &'a [Mino],
+    objective: PackObjective,
+    limit: usize,
+    best: Packing,
+}
+
+impl PackSearch<'_> {
+    /// Depth first search putting and taking back minos on `board`, which is
+    /// left as it was.
+    fn search(
+        &mut self,
+        board: &mut Board,
+        index: usize,
+        score: usize,
+        unplaced: &mut Vec<Mino>,
+        previous_skipped: bool,
+    ) {
+        if score > self.best.score {
+            let mut rest = unplaced.clone();
+            rest.extend_from_slice(&self.minos[index..]);
+            self.best = Packing {
+                board: board.clone(),
+                score,
+                unplaced: rest,
+            };
+        }
+        if index == self.minos.len() || self.best.score == self.limit {
+            return;
+        }
+        let remaining = &self.minos[index..];
+        if score + self.objective.bound(remaining, board.shape.count_vacant()) <= self.best.score {
+            return;
+        }
+        let mino = &self.minos[index];
+        // Identical minos are interchangeable, so once one of them is skipped
+        // the following copies are skipped too.
+        if !(previous_skipped && self.minos[index - 1] == *mino) {
+            for t in board.search_can_put(mino) {
+                board.push_mino(mino.clone(), t);
+                let new_score = score + self.objective.score(mino);
+                self.search(board, index + 1, new_score, unplaced, false);
+                board.unplace_mino(board.mino_transforms.len() - 1);
+                if self.best.score == self.limit {
+                    return;
+                }
+            }
+        }
+        unplaced.push(mino.clone());
+        self.search(board, index + 1, score, unplaced, true);
+        unplaced.pop();
+    }
+}
+
+impl Board {
+    /// Place a subset of `minos` maximising `objective`, leaving the other cells vacant.
+    /// Branch and bound over the same placement search as [`Board::tile_parallel`].
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino, packing::PackObjective};
+    /// let board = Board::from_str("...\n...").unwrap();
+    /// let minos = vec![
+    ///     Mino::from_str("aa\naa").unwrap(),
+    ///     Mino::from_str("bbb").unwrap(),
+    /// ];
+    /// let packing = board.pack(&minos, PackObjective::Area);
+    /// assert_eq!(packing.score, 4);
+    /// assert_eq!(packing.unplaced, vec![Mino::from_str("bbb").unwrap()]);
+    /// ```
+    pub fn pack(&self, minos: &[Mino], objective: PackObjective) -> Packing {
+        let mut search = PackSearch {
+            minos,
+            objective,
+            limit: objective.bound(minos, self.shape.count_vacant()),
+            best: Packing {
+                board: self.clone(),
+                score: 0,
+                unplaced: minos.to_vec(),
+            },
+        };
+        search.search(&mut self.clone(), 0, 0, &mut vec![], false);
+        search.best
+    }
+}
+
+#[test]
+fn test_pack_full_tiling() {
+    let board = Board::from_text_path("data/bench/board.txt").unwrap();
+    let minos = Mino::minos_from_path("data/bench/minos.txt").unwrap();
+    let area = board.shape.count_vacant();
+    let packing = board.pack(&minos, PackObjective::Area);
+    assert_eq!(packing.score, area);
+    assert!(packing.unplaced.is_empty());
+}
+
+#[test]
+fn test_pack_pieces() {
+    // Only one of the two big pieces fits, but both small ones do as well.
+    let board = Board::from_str("....\n....").unwrap();
+    let minos = vec![
+        Mino::from_str("aaa\naaa").unwrap(),
+        Mino::from_str("bbb\nbbb").unwrap(),
+        Mino::from_str("c").unwrap(),
+        Mino::from_str("d").unwrap(),
+    ];
+    let by_area = board.pack(&minos, PackObjective::Area);
+    assert_eq!(by_area.score, 8);
+    let by_pieces = board.pack(&minos, PackObjective::Pieces);
+    assert_eq!(by_pieces.score, 3);
+    assert_eq!(by_pieces.unplaced.len(), 1);
+    assert_eq!(by_pieces.board.mino_transforms().len(), 3);
+    assert_eq!(PackObjective::from_str("pieces"), Ok(PackObjective::Pieces));
+    assert!(PackObjective::from_str("cells").is_err());
+}
+
+#[test]
+fn test_pack_nothing_fits() {
+    let board = Board::from_str("..\n..").unwrap();
+    let minos = vec![Mino::from_str("aaa").unwrap()];
+    let packing = board.pack(&minos, PackObjective::Pieces);
+    assert_eq!(packing.score, 0);
+    assert_eq!(packing.board, board);
+    assert_eq!(packing.unplaced, minos);
+}
@@ -0,0 +1,157 @@
+use crate::topology::Topology;
+use crate::{Board, Mino, Orientation, Shape};
+use std::collections::BTreeSet;
+
+/// Largest modulus tried by the colouring argument.
+const MAX_MODULUS: usize = 8;
+
+impl Board {
+    /// A proof that `minos` cannot tile the vacant cells exactly once each,
+    /// found without searching, or `None` when no quick argument applies.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::{Board, Mino};
+    /// // Two opposite corners of a 4x4 board can not be covered by dominoes.
+    /// let board = Board::from_str("#...\n....\n....\n...#").unwrap();
+    /// let minos = vec![Mino::from_str("aa").unwrap(); 7];
+    /// assert!(board.infeasibility(&minos).unwrap().starts_with("Checkerboard"));
+    /// ```
+    pub fn infeasibility(&self, minos: &[Mino]) -> Option<String> {
+        let area: usize = minos.iter().map(|m| m.count_wall()).sum();
+        if area != self.shape.count_vacant() {
+            return Some(format!(
+                "Area: the minos cover {} cells but the board has {} vacant cells",
+                area,
+                self.shape.count_vacant()
+            ));
+        }
+        // Colourings are only consistent across the edges of a plane board.
+        if self.topology() != Topology::Plane {
+            return None;
+        }
+        self.checkerboard_infeasibility(minos).or_else(|| {
+            (3..=MAX_MODULUS).find_map(|k| {
+                self.coloring_infeasibility(minos, k, |x, y| x + y)
+                    .or_else(|| self.coloring_infeasibility(minos, k, |x, y| x + k - y % k))
+            })
+        })
+    }
+    /// [`Board::tile_parallel`] unless [`Board::infeasibility`] proves there is no tiling.
+    /// The error explains why the board can not be tiled.
+    pub fn tile_or_explain(&self, minos: &[Mino]) -> Result<Self, String> {
+        if let Some(reason) = self.infeasibility(minos) {
+            return Err(reason);
+        }
+        self.tile_parallel(minos)
+            .ok_or("No tiling found by the search".to_string())
+    }
+    /// Every mino covers `d` more cells of one checkerboard colour than the other,
+    /// with the sign depending on where it is put. The signed differences must add up
+    /// to the difference of the vacant cells.
+    fn checkerboard_infeasibility(&self, minos: &[Mino]) -> Option<String> {
+        let (dark, light) = self.shape.inverted().checkerboard_balance();
+        let target = dark as isize - light as isize;
+        let mut reachable = BTreeSet::from([0isize]);
+        for mino in minos {
+            let (a, b) = mino.shape.checkerboard_balance();
+            let d = a as isize - b as isize;
+            reachable = reachable
+                .iter()
+                .flat_map(|&sum| [sum + d, sum - d])
+                .collect();
+        }
+        if reachable.contains(&target) {
+            return None;
+        }
+        Some(format!(
+            "Checkerboard: the vacant cells have {} dark and {} light cells, \
+            a difference the minos can not make",
+            dark, light
+        ))
+    }
+    /// When every placement of every mino covers each of the `k` colours given by
+    /// `color(x, y) % k` equally often, the vacant cells must have each colour equally often.
+    fn coloring_infeasibility(
+        &self,
+        minos: &[Mino],
+        k: usize,
+        color: impl Fn(usize, usize) -> usize,
+    ) -> Option<String> {
+        let counts = |shape: &Shape| {
+            let mut counts = vec![0; k];
+            for (x, y, b) in shape.coordinates() {
+                if b {
+                    counts[color(x, y) % k] += 1;
+                }
+            }
+            counts
+        };
+        let balanced = |counts: &[usize]| counts.iter().all(|&n| n == counts[0]);
+        let every_placement_balanced = minos.iter().all(|mino| {
+            Orientation::all()
+                .iter()
+                .all(|o| balanced(&counts(&mino.shape.oriented(o))))
+        });
+        let vacant = counts(&self.shape.inverted());
+        if !every_placement_balanced || balanced(&vacant) {
+            return None;
+        }
+        Some(format!(
+            "Colouring mod {}: every mino covers each colour equally, \
+            but the vacant cells have {:?} cells of each colour",
+            k, vacant
+        ))
+    }
+}
+
+#[test]
+fn test_checkerboard_infeasibility() {
+    use std::str::FromStr;
+    // Five T-tetrominoes each cover 3 cells of one colour and 1 of the other,
+    // so they can not cover the balanced 4x5 rectangle.
+    let board = Board::from_str(&["....."; 4].join("\n")).unwrap();
+    let minos = vec![Mino::from_str("aaa\n.a.").unwrap(); 5];
+    assert!(
+        board
+            .infeasibility(&minos)
+            .unwrap()
+            .starts_with("Checkerboard")
+    );
+    assert!(board.tile_or_explain(&minos).is_err());
+    let mut minos = vec![Mino::from_str("aaa\n.a.").unwrap(); 4];
+    minos.push(Mino::from_str("bbbb").unwrap());
+    assert_eq!(board.infeasibility(&minos), None);
+}
+
+#[test]
+fn test_coloring_infeasibility() {
+    use std::str::FromStr;
+    // Straight trominoes can not cover an 8x8 board without a corner.
+    let mut rows = ["........"; 8];
+    rows[0] = "#.......";
+    let board = Board::from_str(&rows.join("\n")).unwrap();
+    let minos = vec![Mino::from_str("aaa").unwrap(); 21];
+    assert!(
+        board
+            .infeasibility(&minos)
+            .unwrap()
+            .starts_with("Colouring mod 3")
+    );
+    // Without (2, 2) instead, neither colouring rules it out.
+    rows[0] = "........";
+    rows[2] = "..#.....";
+    let board = Board::from_str(&rows.join("\n")).unwrap();
+    assert_eq!(board.infeasibility(&minos), None);
+}
+
+#[test]
+fn test_infeasibility_of_area() {
+    use std::str::FromStr;
+    let board = Board::from_str("...").unwrap();
+    let minos = vec![Mino::from_str("aa").unwrap()];
+    assert_eq!(
+        board.tile_or_explain(&minos).unwrap_err(),
+        "Area: the minos cover 2 cells but the board has 3 vacant cells"
+    );
+}
@@ -0,0 +1,261 @@
+use crate::Mino;
+use crate::colors::split_colors;
+use crate::constraints::Constraints;
+use crate::cover::Supply;
+use nu_ansi_term::Color;
+use std::str::FromStr;
+
+/// First line of a version 2 piece file, after blank and comment lines.
+const VERSION_LINE: &str = "version: 2";
+
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with("//")
+}
+
+/// Whether `lines` are a version 2 piece file rather than the original format.
+pub(crate) fn is_version_2(lines: &[String]) -> bool {
+    lines
+        .iter()
+        .find(|line| !line.trim().is_empty() && !is_comment(line))
+        .is_some_and(|line| line.trim() == VERSION_LINE)
+}
+
+/// A colour name for [`Mino::display_color`], as `nu_ansi_term` names them.
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    match s.trim().to_lowercase().as_str() {
+        "black" => Ok(Color::Black),
+        "red" => Ok(Color::Red),
+        "green" => Ok(Color::Green),
+        "yellow" => Ok(Color::Yellow),
+        "blue" => Ok(Color::Blue),
+        "purple" | "magenta" => Ok(Color::Purple),
+        "cyan" => Ok(Color::Cyan),
+        "white" => Ok(Color::White),
+        _ => Err(format!("Unknown colour '{}'", s.trim())),
+    }
+}
+
+/// Read a version 2 piece file. After the `version: 2` line, each block starts
+/// with a header line of its name in brackets, followed by `key: value` lines and
+/// the rows of the mino, optionally with colours after '=' as in [`Mino::from_str`].
+/// Blank lines and lines starting with `//` are comments.
+///
+/// - `quantity: 1..2` is the [`Supply`], one copy when left out
+/// - `symbol: f` is the character for the mino on boards; by default the character
+///   of its rows, or the first of its name when the rows use '#'. No two pieces may
+///   have the same symbol
+/// - `colour: red` is the colour it is printed in
+/// - the keys of [`Constraints::read_line`], like `orientations: all`
+///
+/// ```
+/// use tiling_mino_solver::{Mino, cover::Supply};
+/// let lines = [
+///     "version: 2",
+///     "// The F pentomino, which may be turned over",
+///     "[F-pentomino]",
+///     "quantity: 1..2",
+///     "colour: red",
+///     "orientations: all",
+///     ".##",
+///     "##.",
+///     ".#.",
+///     "[bar]",
+///     "bbb",
+/// ];
+/// let supplies = Mino::supplies_from_lines(lines.iter().map(|s| s.to_string()).collect()).unwrap();
+/// let (f, supply) = &supplies[0];
+/// assert_eq!((f.name, f.label.as_deref()), ('F', Some("F-pentomino")));
+/// assert_eq!(*supply, Supply { min: 1, max: Some(2) });
+/// assert_eq!(f.orientations().len(), 8);
+/// assert_eq!(supplies[1].0.name, 'b');
+/// ```
+pub(crate) fn read_supplies(lines: &[String]) -> Result<Vec<(Mino, Supply)>, String> {
+    let mut blocks: Vec<(String, usize, Vec<&str>)> = vec![];
+    let mut version_seen = false;
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || is_comment(line) {
+            continue;
+        }
+        if !version_seen {
+            version_seen = true;
+            continue;
+        }
+        if let Some(name) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            blocks.push((name.trim().to_string(), i + 1, vec![]));
+        } else if let Some((_, _, block)) = blocks.last_mut() {
+            block.push(trimmed);
+        } else {
+            return Err(format!(
+                "Line {}: expected a header like '[name]', found '{}'",
+                i + 1,
+                trimmed
+            ));
+        }
+    }
+    let mut supplies: Vec<(Mino, Supply)> = vec![];
+    for (name, line, block) in &blocks {
+        let error = |e: String| format!("Piece '{}' at line {}: {}", name, line, e);
+        let (mino, supply) = read_block(name, block).map_err(error)?;
+        if let Some((other, _)) = supplies.iter().find(|(m, _)| m.name == mino.name) {
+            return Err(error(format!(
+                "The symbol '{}' is already used by piece '{}', give one of them a 'symbol:'",
+                mino.name,
+                other.label.as_deref().unwrap_or_default()
+            )));
+        }
+        supplies.push((mino, supply));
+    }
+    Ok(supplies)
+}
+
+fn read_block(name: &str, block: &[&str]) -> Result<(Mino, Supply), String> {
+    if name.is_empty() {
+        return Err("The name is empty".to_string());
+    }
+    let n_keys = block.iter().take_while(|line| line.contains(':')).count();
+    let mut supply = Supply::exactly(1);
+    let mut symbol = None;
+    let mut display_color = None;
+    let mut constraints = Constraints::default();
+    for line in &block[..n_keys] {
+        let (key, value) = line.split_once(':').unwrap_or_default();
+        match key.trim() {
+            "quantity" => supply = Supply::from_str(value)?,
+            "symbol" => {
+                let mut chars = value.trim().chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c != '.' => symbol = Some(c),
+                    _ => return Err(format!("Invalid symbol '{}'", value.trim())),
+                }
+            }
+            "colour" | "color" => {
+                parse_color(value)?;
+                display_color = Some(value.trim().to_string());
+            }
+            _ => constraints.read_line(line)?,
+        }
+    }
+    let (rows, colors) = split_colors(&block[n_keys..].join("\n"));
+    if rows.trim().is_empty() {
+        return Err("The piece has no rows".to_string());
+    }
+    let symbol = symbol.unwrap_or_else(|| {
+        let mut cells = rows.chars().filter(|&c| c != '.' && c != '\n');
+        match cells.next() {
+            Some(c) if c != '#' && cells.all(|d| d == c) => c,
+            _ => name.chars().next().unwrap_or_default(),
+        }
+    });
+    let mut s: String = rows
+        .chars()
+        .map(|c| if c == '.' || c == '\n' { c } else { symbol })
+        .collect();
+    if let Some(colors) = colors {
+        s = format!("{}\n=\n{}", s, colors);
+    }
+    let mut mino = Mino::from_str(&s)?;
+    mino.constraints = constraints;
+    mino.label = Some(name.to_string());
+    mino.display_color = display_color;
+    Ok((mino, supply))
+}
+
+impl Mino {
+    /// Inverse of reading a version 2 piece file with [`Mino::supplies_from_lines`].
+    /// Minos without a label are named by their symbol.
+    pub fn supplies_to_v2_lines(supplies: &[(Mino, Supply)]) -> Vec<String> {
+        let mut lines = vec![VERSION_LINE.to_string()];
+        for (mino, supply) in supplies {
+            lines.push(String::new());
+            lines.push(format!(
+                "[{}]",
+                mino.label.clone().unwrap_or(mino.name.to_string())
+            ));
+            if *supply != Supply::exactly(1) {
+                lines.push(format!("quantity: {}", supply));
+            }
+            lines.push(format!("symbol: {}", mino.name));
+            if let Some(color) = &mino.display_color {
+                lines.push(format!("colour: {}", color));
+            }
+            lines.extend(mino.constraints.to_lines());
+            lines.extend(mino.to_lines());
+        }
+        lines
+    }
+}
+
+#[test]
+fn test_read_version_2() {
+    let text = "\
+// Two pieces
+version: 2
+
+[long bar]
+quantity: *
+colour: Blue
+border: true
+####
+// The other
+[z]
+symbol: s
+orientations: neutral right
+zz.
+.zz
+=
+BW.
+.BW
+";
+    let lines: Vec<String> = text.lines().map(String::from).collect();
+    let supplies = Mino::supplies_from_lines(lines).unwrap();
+    let (bar, supply) = &supplies[0];
+    assert_eq!(bar.name, 'l');
+    assert_eq!(bar.label.as_deref(), Some("long bar"));
+    assert_eq!(*supply, Supply::unlimited());
+    assert_eq!(
+        parse_color(bar.display_color.as_ref().unwrap()),
+        Ok(Color::Blue)
+    );
+    assert!(bar.constraints.touch_border);
+    let (z, supply) = &supplies[1];
+    assert_eq!(z.to_lines(), vec!["ss.", ".ss", "=", "BW.", ".BW"]);
+    assert_eq!(*supply, Supply::exactly(1));
+    assert_eq!(z.orientations().len(), 2);
+    let lines = Mino::supplies_to_v2_lines(&supplies);
+    assert_eq!(Mino::supplies_from_lines(lines).unwrap(), supplies);
+}
+
+#[test]
+fn test_read_version_2_errors() {
+    let read = |text: &str| Mino::supplies_from_lines(text.lines().map(String::from).collect());
+    assert_eq!(
+        read("version: 2\naa").unwrap_err(),
+        "Line 2: expected a header like '[name]', found 'aa'"
+    );
+    assert_eq!(
+        read("version: 2\n[a]\ncolour: mauve\naa").unwrap_err(),
+        "Piece 'a' at line 2: Unknown colour 'mauve'"
+    );
+    assert_eq!(
+        read("version: 2\n[a]\nquantity: 1\n[b]\nb").unwrap_err(),
+        "Piece 'a' at line 2: The piece has no rows"
+    );
+    assert!(read("version: 2\n[a]\na.a").is_err());
+    assert_eq!(
+        read("version: 2\n[L one]\n#.\n##\n[L two]\n##\n#.").unwrap_err(),
+        "Piece 'L two' at line 5: The symbol 'L' is already used by piece 'L one', \
+        give one of them a 'symbol:'"
+    );
+    assert!(read("version: 2\n[L one]\n#.\n##\n[L two]\nsymbol: M\n##\n#.").is_ok());
+}
+
+#[test]
+fn test_original_format_still_read() {
+    let minos = Mino::minos_from_path("data/minos").unwrap();
+    assert!(!minos.is_empty());
+    assert!(minos.iter().all(|mino| mino.label.is_none()));
+}
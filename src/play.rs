@@ -0,0 +1,384 @@
+use crate::hint::Hint;
+use crate::{Board, Mino, Orientation, Rotation, TransForm};
+use nu_ansi_term::{Color, Style};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// A move of the player in [`Play::apply`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    /// Select the next of the remaining minos.
+    Next,
+    /// Select the previous of the remaining minos.
+    Previous,
+    /// Turn the selected mino a quarter clockwise.
+    Rotate,
+    /// Turn the selected mino over.
+    Flip,
+    /// Move the cursor by `(dx, dy)`, staying on the board.
+    Move(isize, isize),
+    /// Put the selected mino at the cursor.
+    Place,
+    /// Take the mino under the cursor off the board.
+    Remove,
+    /// Go back to the position before the last place or remove.
+    Undo,
+    /// Place or remove again what the last undo took back.
+    Redo,
+    /// Show a placement which keeps the position solvable, waiting for the check
+    /// of the position to finish.
+    Hint,
+}
+
+/// A puzzle being played: the board, the minos left to place, the selected mino
+/// with its orientation, and the cursor where it would be put. Undo and redo are
+/// those of the board, [`Board::undo`] and [`Board::redo`].
+///
+/// Whether the position is still solvable is checked by [`Board::hint`] on another
+/// thread after every change, so that a long search does not block the player.
+#[derive(Debug)]
+pub struct Play {
+    board: Board,
+    /// Every mino of the puzzle, on the board or not.
+    pieces: Vec<Mino>,
+    /// The minos of `pieces` which are not on the board.
+    remaining: Vec<Mino>,
+    selected: usize,
+    orientation: Orientation,
+    cursor: (usize, usize),
+    /// Found by the last check, `None` while the position is being checked.
+    status: Option<Hint>,
+    check: Option<Check>,
+    message: String,
+}
+
+/// A running [`Board::hint`], cancelled when it is dropped.
+#[derive(Debug)]
+struct Check {
+    cancel: Arc<AtomicBool>,
+    result: Receiver<Option<Hint>>,
+}
+
+impl Check {
+    fn start(board: &Board, remaining: &[Mino]) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (sender, result) = mpsc::channel();
+        let (board, remaining, flag) = (board.clone(), remaining.to_vec(), cancel.clone());
+        thread::spawn(move || {
+            // The receiver is gone when the check was dropped.
+            let _ = sender.send(board.hint_unless_cancelled(&remaining, &flag));
+        });
+        Self { cancel, result }
+    }
+}
+
+impl Drop for Check {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The next rotation clockwise.
+fn clockwise(rotation: &Rotation) -> Rotation {
+    match rotation {
+        Rotation::Neutral => Rotation::Right,
+        Rotation::Right => Rotation::OneEighty,
+        Rotation::OneEighty => Rotation::Left,
+        Rotation::Left => Rotation::Neutral,
+    }
+}
+
+impl Play {
+    pub fn new(board: Board, remaining: Vec<Mino>) -> Self {
+        let mut pieces: Vec<Mino> = board
+            .mino_transforms()
+            .iter()
+            .map(|(mino, _)| mino.clone())
+            .collect();
+        pieces.extend(remaining.iter().cloned());
+        let check = Some(Check::start(&board, &remaining));
+        Self {
+            board,
+            pieces,
+            remaining,
+            selected: 0,
+            orientation: Orientation::rotations()[0].clone(),
+            cursor: (0, 0),
+            status: None,
+            check,
+            message: String::new(),
+        }
+    }
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+    pub fn remaining(&self) -> &[Mino] {
+        &self.remaining
+    }
+    /// Whether the position is solved, still solvable or lost, as found by [`Board::hint`].
+    /// `None` while the check of the position since the last change is running.
+    pub fn status(&self) -> Option<&Hint> {
+        self.status.as_ref()
+    }
+    /// Take the result of the running check if it is done. Returns whether the status
+    /// changed, so that the screen needs to be drawn again.
+    pub fn poll(&mut self) -> bool {
+        let Some(check) = &self.check else {
+            return false;
+        };
+        match check.result.try_recv() {
+            Ok(status) => {
+                self.status = status;
+                self.check = None;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+    /// Wait for the running check to finish.
+    pub fn wait(&mut self) {
+        if let Some(check) = self.check.take() {
+            self.status = check.result.recv().ok().flatten();
+        }
+    }
+    fn transform(&self) -> TransForm {
+        TransForm::new(self.cursor.0, self.cursor.1, self.orientation.clone())
+    }
+    /// Cells the selected mino would cover at the cursor, and whether it may be put there.
+    fn preview(&self) -> Option<(Vec<(usize, usize)>, bool)> {
+        let mino = self.remaining.get(self.selected)?;
+        let transform = self.transform();
+        let cells = self
+            .board
+            .covered_cells(&mino.oriented(&self.orientation), &transform)?;
+        let allowed = mino.orientations().contains(&self.orientation)
+            && self.board.can_put(mino, &transform)
+            && mino.constraints.allows(&self.board, &cells);
+        Some((cells, allowed))
+    }
+    /// Select the `index`-th remaining mino, unturned.
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.orientation = Orientation::rotations()[0].clone();
+    }
+    /// Update the remaining minos after the board changed, and check the new position
+    /// instead of the old one.
+    fn changed(&mut self) {
+        self.remaining = self
+            .board
+            .unplaced_minos(&self.pieces)
+            .expect("the board only has minos of the puzzle");
+        self.select(self.selected.min(self.remaining.len().saturating_sub(1)));
+        self.status = None;
+        self.check = Some(Check::start(&self.board, &self.remaining));
+    }
+    pub fn apply(&mut self, action: Action) {
+        self.message.clear();
+        let n = self.remaining.len().max(1);
+        match action {
+            Action::Next => self.select((self.selected + 1) % n),
+            Action::Previous => self.select((self.selected + n - 1) % n),
+            Action::Rotate => self.orientation.rotation = clockwise(&self.orientation.rotation),
+            Action::Flip => self.orientation.flipped = !self.orientation.flipped,
+            Action::Move(dx, dy) => {
+                let (x, y) = self.cursor;
+                self.cursor = (
+                    x.saturating_add_signed(dx)
+                        .min(self.board.width().saturating_sub(1)),
+                    y.saturating_add_signed(dy)
+                        .min(self.board.height().saturating_sub(1)),
+                );
+            }
+            Action::Place => match self.preview() {
+                Some((_, true)) => {
+                    let mino = self.remaining[self.selected].clone();
+                    self.board.put_mino(mino, self.transform());
+                    self.changed();
+                }
+                _ => self.message = "The mino can not be put here".to_string(),
+            },
+            Action::Remove => {
+                let (x, y) = self.cursor;
+                match self.board.mino_at(x, y) {
+                    Some(index) if index >= self.board.fixed => {
+                        self.board.remove_mino(index);
+                        self.changed();
+                    }
+                    Some(_) => self.message = "The mino is fixed".to_string(),
+                    None => self.message = "No mino here".to_string(),
+                }
+            }
+            Action::Undo => {
+                if self.board.undo() {
+                    self.changed();
+                } else {
+                    self.message = "Nothing to undo".to_string();
+                }
+            }
+            Action::Redo => {
+                if self.board.redo() {
+                    self.changed();
+                } else {
+                    self.message = "Nothing to redo".to_string();
+                }
+            }
+            Action::Hint => {
+                self.wait();
+                let Some(status) = &self.status else {
+                    return;
+                };
+                self.message = match status {
+                    Hint::Place(mino, transform) => format!(
+                        "Try '{}' at ({}, {}) turned {}",
+                        mino.name,
+                        transform.x(),
+                        transform.y(),
+                        transform.orientation()
+                    ),
+                    Hint::Solved => "Solved".to_string(),
+                    Hint::Unsolvable(reason) => reason.clone(),
+                };
+            }
+        }
+    }
+    /// The screen: the board in the colours of [`Board::pretty_print`] with the selected
+    /// mino previewed at the cursor, the remaining minos, the status and the last message.
+    pub fn lines(&self) -> Vec<String> {
+        let colors = self.board.mino_colors();
+        let preview = self.preview();
+        let mut lines: Vec<String> = self
+            .board
+            .char_matrix()
+            .into_iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.into_iter()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        let style = match &preview {
+                            Some((cells, allowed)) if cells.contains(&(x, y)) => {
+                                let name = self.remaining[self.selected].name;
+                                let color = if *allowed { Color::Green } else { Color::Red };
+                                return Color::Black.on(color).paint(name.to_string()).to_string();
+                            }
+                            _ if (x, y) == self.cursor => Style::new().reverse(),
+                            _ if c == '#' => Color::Black.on(Color::White),
+                            _ if c == '.' => Style::new(),
+                            _ => colors.get(&c).copied().unwrap_or(Color::White).normal(),
+                        };
+                        style.paint(c.to_string()).to_string()
+                    })
+                    .collect()
+            })
+            .collect();
+        lines.push(String::new());
+        let names: Vec<String> = self
+            .remaining
+            .iter()
+            .enumerate()
+            .map(|(i, mino)| {
+                if i == self.selected {
+                    format!("[{}]", mino.name)
+                } else {
+                    mino.name.to_string()
+                }
+            })
+            .collect();
+        lines.push(format!(
+            "Minos: {}  turned {}",
+            names.join(" "),
+            self.orientation
+        ));
+        lines.push(match &self.status {
+            Some(Hint::Place(..)) => Color::Green.paint("Solvable").to_string(),
+            Some(Hint::Solved) => Color::Green.bold().paint("Solved").to_string(),
+            Some(Hint::Unsolvable(_)) => Color::Red.paint("Not solvable any more").to_string(),
+            None => Color::Yellow.paint("Checking…").to_string(),
+        });
+        lines.push(self.message.clone());
+        lines
+    }
+}
+
+#[test]
+fn test_play_place_remove_undo() {
+    use std::str::FromStr;
+    let board = Board::from_str("...\n...").unwrap();
+    let minos = vec![
+        Mino::from_str("aa\na.").unwrap(),
+        Mino::from_str("bb\nb.").unwrap(),
+    ];
+    let mut play = Play::new(board, minos);
+    play.wait();
+    assert!(matches!(play.status(), Some(Hint::Place(..))));
+    // The L in the middle cuts off the left column.
+    play.apply(Action::Move(1, 0));
+    play.apply(Action::Place);
+    assert_eq!(play.board().pretty_shape(), ".aa\n.a.");
+    play.wait();
+    assert!(matches!(play.status(), Some(Hint::Unsolvable(_))));
+    play.apply(Action::Place);
+    assert_eq!(play.message, "The mino can not be put here");
+    play.apply(Action::Remove);
+    assert_eq!(play.board().pretty_shape(), "...\n...");
+    assert_eq!(play.remaining().len(), 2);
+    play.wait();
+    assert!(matches!(play.status(), Some(Hint::Place(..))));
+    play.apply(Action::Undo);
+    assert_eq!(play.board().pretty_shape(), ".aa\n.a.");
+    play.wait();
+    assert!(matches!(play.status(), Some(Hint::Unsolvable(_))));
+    play.apply(Action::Hint);
+    assert_eq!(play.message, "No solution continues from this position");
+    play.apply(Action::Undo);
+    assert_eq!(play.board().pretty_shape(), "...\n...");
+    play.apply(Action::Hint);
+    assert!(play.message.starts_with("Try "));
+    assert!(matches!(play.status(), Some(Hint::Place(..))));
+    play.apply(Action::Undo);
+    assert_eq!(play.message, "Nothing to undo");
+    play.apply(Action::Redo);
+    assert_eq!(play.board().pretty_shape(), ".aa\n.a.");
+    assert_eq!(play.remaining().len(), 1);
+    play.apply(Action::Redo);
+    assert_eq!(play.board().pretty_shape(), "...\n...");
+    assert_eq!(play.remaining().len(), 2);
+    play.apply(Action::Redo);
+    assert_eq!(play.message, "Nothing to redo");
+}
+
+#[test]
+fn test_play_rotate_and_flip() {
+    use std::str::FromStr;
+    let board = Board::from_str("..\n..\n..").unwrap();
+    let l = Mino {
+        constraints: crate::constraints::Constraints {
+            orientations: Some(Orientation::all()),
+            ..Default::default()
+        },
+        ..Mino::from_str("a.\na.\naa").unwrap()
+    };
+    let mut play = Play::new(board, vec![l, Mino::from_str("b\nb").unwrap()]);
+    play.apply(Action::Flip);
+    play.apply(Action::Place);
+    assert_eq!(play.board().pretty_shape(), ".a\n.a\naa");
+    play.apply(Action::Next);
+    play.apply(Action::Rotate);
+    play.apply(Action::Place);
+    assert_eq!(play.message, "The mino can not be put here");
+    play.apply(Action::Rotate);
+    play.apply(Action::Place);
+    assert_eq!(play.board().pretty_shape(), "ba\nba\naa");
+    play.wait();
+    assert_eq!(play.status(), Some(&Hint::Solved));
+}
+
+#[test]
+fn test_play_move_on_empty_board() {
+    use crate::Shape;
+    let mut play = Play::new(Board::new(Shape::new(vec![])), vec![]);
+    play.apply(Action::Move(1, 1));
+    assert_eq!(play.cursor, (0, 0));
+}
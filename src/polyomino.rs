@@ -0,0 +1,182 @@
+use crate::gui::Scale;
+use crate::{Mino, Orientation, Shape};
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+/// Which placements of a polyomino count as the same piece.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Equivalence {
+    /// Same up to translation, rotation and reflection.
+    Free,
+    /// Same up to translation and rotation.
+    OneSided,
+    /// Same up to translation.
+    Fixed,
+}
+
+impl FromStr for Equivalence {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "free" => Ok(Equivalence::Free),
+            "one-sided" | "one_sided" => Ok(Equivalence::OneSided),
+            "fixed" => Ok(Equivalence::Fixed),
+            _ => Err(format!("Unknown equivalence '{}'", s)),
+        }
+    }
+}
+
+/// Every polyomino of `n` cells, one per class of `equivalence`, grown cell by cell
+/// from the forms given by [`Shape::canonical`].
+///
+/// ```
+/// use tiling_mino_solver::polyomino::{Equivalence, polyominoes};
+/// assert_eq!(polyominoes(4, Equivalence::Free).len(), 5);
+/// assert_eq!(polyominoes(4, Equivalence::OneSided).len(), 7);
+/// assert_eq!(polyominoes(4, Equivalence::Fixed).len(), 19);
+/// ```
+pub fn polyominoes(n: usize, equivalence: Equivalence) -> Vec<Shape> {
+    if n == 0 {
+        return vec![];
+    }
+    let mut current: BTreeSet<Shape> = BTreeSet::from([Shape(vec![vec![true]])]);
+    for _ in 1..n {
+        let mut next = BTreeSet::new();
+        for shape in &current {
+            let mut padded = shape.clone();
+            padded.pad(1, 1, 1, 1);
+            let is_wall = |x: usize, y: usize| {
+                x < padded.width() && y < padded.height() && padded.is_wall(x, y)
+            };
+            for (x, y, b) in padded.coordinates() {
+                let touches = is_wall(x.wrapping_sub(1), y)
+                    || is_wall(x + 1, y)
+                    || is_wall(x, y.wrapping_sub(1))
+                    || is_wall(x, y + 1);
+                if b || !touches {
+                    continue;
+                }
+                let mut grown = padded.clone();
+                grown.put_on(x, y, true);
+                next.insert(grown.canonical(equivalence));
+            }
+        }
+        current = next;
+    }
+    current.into_iter().collect()
+}
+
+/// Names given to generated minos, in order: only ASCII letters, which are typed
+/// and shown the same everywhere and are not confused with counts in minos files.
+fn names() -> impl Iterator<Item = char> {
+    ('a'..='z').chain('A'..='Z')
+}
+
+/// [`polyominoes`] as minos named 'a' to 'z' and then 'A' to 'Z' in order, which may
+/// be turned as `equivalence` says: free minos may also be turned over, and fixed
+/// ones not at all. Fails when there are more minos than names.
+pub fn generate_minos(n: usize, equivalence: Equivalence) -> Result<Vec<Mino>, String> {
+    let orientations = match equivalence {
+        Equivalence::Free => Some(Orientation::all()),
+        Equivalence::OneSided => None,
+        Equivalence::Fixed => Some(Orientation::rotations()[..1].to_vec()),
+    };
+    let shapes = polyominoes(n, equivalence);
+    if shapes.len() > names().count() {
+        return Err(format!(
+            "{} polyominoes of size {} are more than the {} letters to name them",
+            shapes.len(),
+            n,
+            names().count()
+        ));
+    }
+    Ok(shapes
+        .into_iter()
+        .zip(names())
+        .map(|(shape, name)| {
+            let mut mino = Mino::new(name, shape);
+            mino.constraints.orientations = orientations.clone();
+            mino
+        })
+        .collect())
+}
+
+#[test]
+fn test_polyomino_counts() {
+    let count = |n, equivalence| polyominoes(n, equivalence).len();
+    let free: Vec<usize> = (0..=7).map(|n| count(n, Equivalence::Free)).collect();
+    assert_eq!(free, vec![0, 1, 1, 2, 5, 12, 35, 108]);
+    let one_sided: Vec<usize> = (1..=6).map(|n| count(n, Equivalence::OneSided)).collect();
+    assert_eq!(one_sided, vec![1, 1, 2, 7, 18, 60]);
+    let fixed: Vec<usize> = (1..=6).map(|n| count(n, Equivalence::Fixed)).collect();
+    assert_eq!(fixed, vec![1, 2, 6, 19, 63, 216]);
+}
+
+#[test]
+fn test_generated_minos_round_trip() {
+    let minos = generate_minos(5, Equivalence::OneSided).unwrap();
+    assert_eq!(minos.len(), 18);
+    let names: BTreeSet<char> = minos.iter().map(|m| m.name).collect();
+    assert_eq!(names.len(), 18);
+    assert!(names.iter().all(|c| c.is_ascii_alphabetic()));
+    assert!(minos.iter().all(|m| m.count_wall() == 5));
+    let lines = Mino::minos_to_lines(&minos);
+    assert_eq!(Mino::minos_from_lines(lines).unwrap(), minos);
+    assert_eq!(
+        generate_minos(5, Equivalence::Fixed).unwrap_err(),
+        "63 polyominoes of size 5 are more than the 52 letters to name them"
+    );
+}
+
+#[test]
+fn test_generated_free_minos_turn_over() {
+    let minos = generate_minos(4, Equivalence::Free).unwrap();
+    let lines = Mino::minos_to_lines(&minos);
+    assert!(lines.iter().any(|line| line == "orientations: all"));
+    let read = Mino::minos_from_lines(lines).unwrap();
+    assert_eq!(read, minos);
+    // The L tetromino has no symmetry, so all 8 orientations differ.
+    let l = read
+        .iter()
+        .find(|m| m.shape.symmetries().len() == 1)
+        .unwrap();
+    assert_eq!(l.orientations().len(), 8);
+    let one_sided = generate_minos(4, Equivalence::OneSided).unwrap();
+    assert!(one_sided.iter().all(|m| m.orientations().len() == 4));
+    let fixed = generate_minos(4, Equivalence::Fixed).unwrap();
+    assert!(fixed.iter().all(|m| m.orientations().len() == 1));
+}
+
+#[test]
+fn test_generated_tetrominoes() {
+    let minos = generate_minos(4, Equivalence::Free).unwrap();
+    let expected = "1
+orientations: all
+..a
+aaa
+------------
+1
+orientations: all
+.b
+bb
+.b
+------------
+1
+orientations: all
+.c
+cc
+c.
+------------
+1
+orientations: all
+d
+d
+d
+d
+------------
+1
+orientations: all
+ee
+ee";
+    assert_eq!(Mino::minos_to_lines(&minos).join("\n"), expected);
+}
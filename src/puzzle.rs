@@ -0,0 +1,294 @@
+use crate::catalog::catalog;
+use crate::constraints::Constraints;
+use crate::cover::{CoverSearch, Supply};
+use crate::topology::Topology;
+use crate::{Board, Mino, Orientation, TransForm, check_subset_wall_count};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::AtomicUsize;
+
+/// How the minos are used to tile the board.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Every mino is placed exactly once.
+    #[default]
+    Exact,
+    /// Any subset of the minos is placed.
+    Subset,
+    /// Each mino is placed as many times as its count range allows.
+    Supply,
+}
+
+/// Where the pieces of a puzzle file come from.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PieceSource {
+    /// A built-in piece set of [`catalog`], one of each.
+    Catalog(String),
+    /// A minos file or directory, relative to the puzzle file.
+    Path(String),
+    /// Lines of a minos text file, in either version.
+    Lines(Vec<String>),
+    /// Minos as in a JSON minos file, one entry per copy.
+    Minos(Vec<Mino>),
+}
+
+/// Rules of a puzzle file, all optional.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug, Default)]
+#[serde(default)]
+pub struct Rules {
+    /// Whether every mino may be turned over. Unset leaves it to each mino.
+    pub flip: Option<bool>,
+    pub mode: Mode,
+    /// A topology name read by [`Topology::from_str`], like `torus`.
+    pub topology: Option<String>,
+    /// Constraints replacing those of the minos with these names.
+    pub constraints: BTreeMap<char, Constraints>,
+}
+
+/// A puzzle file: the board as text lines, with letters for minos already on it
+/// and optional target colours, the pieces, and the rules.
+#[derive(Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct PuzzleFile {
+    pub board: Vec<String>,
+    pub pieces: PieceSource,
+    #[serde(default)]
+    pub rules: Rules,
+}
+
+/// A puzzle ready to solve: the board with its fixed minos and topology, and the
+/// minos to put on it with the rules applied.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Puzzle {
+    pub board: Board,
+    /// Every mino kind with how many copies may be used, including those on the board.
+    pub supplies: Vec<(Mino, Supply)>,
+    pub mode: Mode,
+}
+
+/// The outcome of solving a puzzle, written as one line of JSON by the CLI.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Report {
+    pub solved: bool,
+    /// The tiled board as by [`Board::to_lines`], when solved.
+    pub board: Option<Vec<String>>,
+    /// Name and transform of every mino on the tiled board.
+    pub placements: Vec<(char, TransForm)>,
+    /// Why there is no tiling, when not solved.
+    pub reason: Option<String>,
+}
+
+impl Report {
+    pub fn new(tiled: &Result<Board, String>) -> Self {
+        match tiled {
+            Ok(board) => Self {
+                solved: true,
+                board: Some(board.to_lines()),
+                placements: board
+                    .mino_transforms()
+                    .iter()
+                    .map(|(mino, transform)| (mino.name, transform.clone()))
+                    .collect(),
+                reason: None,
+            },
+            Err(reason) => Self {
+                solved: false,
+                board: None,
+                placements: vec![],
+                reason: Some(reason.clone()),
+            },
+        }
+    }
+}
+
+impl PieceSource {
+    fn supplies(&self, base: &Path) -> Result<Vec<(Mino, Supply)>, String> {
+        match self {
+            PieceSource::Catalog(name) => Ok(catalog(name)?
+                .into_iter()
+                .map(|mino| (mino, Supply::exactly(1)))
+                .collect()),
+            PieceSource::Path(path) => Mino::supplies_from_path(base.join(path)),
+            PieceSource::Lines(lines) => Mino::supplies_from_lines(lines.clone()),
+            PieceSource::Minos(minos) => Ok(minos
+                .chunk_by(|a, b| a == b)
+                .map(|chunk| (chunk[0].clone(), Supply::exactly(chunk.len())))
+                .collect()),
+        }
+    }
+}
+
+impl Puzzle {
+    pub fn new(board: Board, supplies: Vec<(Mino, Supply)>, mode: Mode) -> Self {
+        Self {
+            board,
+            supplies,
+            mode,
+        }
+    }
+    /// Load a puzzle file. Piece paths in it are relative to the directory of `path`.
+    pub fn from_path<P>(path: P) -> Result<Self, String>
+    where
+        P: AsRef<Path>,
+    {
+        let buf = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let base = path.as_ref().parent().unwrap_or(Path::new(""));
+        Self::from_json_str(&buf, base).map_err(|e| format!("{}: {}", path.as_ref().display(), e))
+    }
+    /// A puzzle from the JSON of a [`PuzzleFile`], with piece paths relative to `base`.
+    ///
+    /// ```
+    /// use std::path::Path;
+    /// use tiling_mino_solver::puzzle::Puzzle;
+    /// let s = r#"{
+    ///     "board": ["aa..", "...."],
+    ///     "pieces": {"lines": ["2", "aa"]},
+    ///     "rules": {"mode": "supply", "topology": "cylinder"}
+    /// }"#;
+    /// let puzzle = Puzzle::from_json_str(s, Path::new("")).unwrap();
+    /// assert!(puzzle.solve().is_err());
+    /// let s = s.replace("\"2\"", "\"4\"");
+    /// let solved = Puzzle::from_json_str(&s, Path::new("")).unwrap().solve().unwrap();
+    /// assert_eq!(solved.pretty_shape(), "aaaa\naaaa");
+    /// ```
+    pub fn from_json_str(s: &str, base: &Path) -> Result<Self, String> {
+        let file: PuzzleFile = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        let rules = &file.rules;
+        let mut supplies = file.pieces.supplies(base)?;
+        for (mino, _) in &mut supplies {
+            match rules.flip {
+                Some(true) => mino.constraints.orientations = Some(Orientation::all()),
+                Some(false) => mino.constraints.orientations = Some(Orientation::rotations()),
+                None => {}
+            }
+            if let Some(constraints) = rules.constraints.get(&mino.name) {
+                mino.constraints = constraints.clone();
+            }
+        }
+        if let Some(name) = rules
+            .constraints
+            .keys()
+            .find(|&&name| supplies.iter().all(|(mino, _)| mino.name != name))
+        {
+            return Err(format!("Constraints are given for unknown mino '{}'", name));
+        }
+        let kinds: Vec<Mino> = supplies.iter().map(|(mino, _)| mino.clone()).collect();
+        let mut board = Board::from_str_with_minos(&file.board.join("\n"), &kinds)?;
+        if let Some(topology) = &rules.topology {
+            board.set_topology(Topology::from_str(topology)?);
+        }
+        board.unplaced_supplies(&supplies)?;
+        Ok(Self::new(board, supplies, rules.mode))
+    }
+    /// The minos left to put on the board, largest first. Fails for counts which
+    /// are not exact, which only [`Mode::Supply`] allows.
+    pub fn unplaced_minos(&self) -> Result<Vec<Mino>, String> {
+        let mut minos = Mino::minos_from_supplies(self.board.unplaced_supplies(&self.supplies)?)?;
+        minos.sort_by_key(|m| m.count_wall());
+        minos.reverse();
+        Ok(minos)
+    }
+    /// Tile the board as the mode says. The error explains why there is no tiling
+    /// when a quick proof is found.
+    pub fn solve(&self) -> Result<Board, String> {
+        match self.mode {
+            Mode::Exact => self.board.tile_or_explain(&self.unplaced_minos()?),
+            _ => self.solve_counting(&AtomicUsize::new(0)),
+        }
+    }
+    /// [`Puzzle::solve`] without printing progress, adding the number of searched
+    /// nodes to `nodes`.
+    pub fn solve_counting(&self, nodes: &AtomicUsize) -> Result<Board, String> {
+        match self.mode {
+            Mode::Exact => {
+                let minos = self.unplaced_minos()?;
+                if let Some(reason) = self.board.infeasibility(&minos) {
+                    return Err(reason);
+                }
+                self.board
+                    .tile_counting(&minos, nodes)
+                    .ok_or("No tiling found by the search".to_string())
+            }
+            Mode::Subset => {
+                let minos = self.unplaced_minos()?;
+                check_subset_wall_count(&minos, &self.board)?;
+                CoverSearch::new(&self.board, &minos)
+                    .with_node_counter(nodes)
+                    .solve()
+                    .map(|search| search.board())
+                    .ok_or("No subset of the minos tiles the board".to_string())
+            }
+            Mode::Supply => CoverSearch::with_supplies(
+                &self.board,
+                &self.board.unplaced_supplies(&self.supplies)?,
+            )
+            .with_node_counter(nodes)
+            .solve()
+            .map(|search| search.board())
+            .ok_or("No tiling found by the search".to_string()),
+        }
+    }
+}
+
+#[test]
+fn test_puzzle_from_path() {
+    let puzzle = Puzzle::from_path("data/puzzle.json").unwrap();
+    assert_eq!(puzzle.mode, Mode::Exact);
+    assert_eq!(puzzle.board.fixed_mino_transforms().len(), 1);
+    let solved = puzzle.solve().unwrap();
+    assert_eq!(solved.mino_transforms().len(), puzzle.supplies.len());
+    assert_eq!(solved.shape.count_vacant(), 0);
+}
+
+#[test]
+fn test_puzzle_rules() {
+    let s = r#"{
+        "board": ["...", "..."],
+        "pieces": {"minos": [
+            {"name": "a", "shape": [[true, true, true]]},
+            {"name": "b", "shape": [[true, true, true]]},
+            {"name": "c", "shape": [[true, false], [true, true]]}
+        ]},
+        "rules": {"flip": true, "mode": "subset", "constraints": {"a": {"forbidden_cells": [[0, 0]]}}}
+    }"#;
+    let puzzle = Puzzle::from_json_str(s, Path::new("")).unwrap();
+    assert_eq!(puzzle.supplies[2].0.orientations().len(), 8);
+    assert_eq!(
+        puzzle.supplies[0].0.constraints.forbidden_cells,
+        vec![(0, 0)]
+    );
+    let solved = puzzle.solve().unwrap();
+    assert_eq!(solved.pretty_shape(), "bbb\naaa");
+    let unknown = s.replace("{\"a\":", "{\"z\":");
+    assert_eq!(
+        Puzzle::from_json_str(&unknown, Path::new("")).unwrap_err(),
+        "Constraints are given for unknown mino 'z'"
+    );
+    let catalog = r#"{"board": ["....."], "pieces": {"catalog": "pentominoes"}}"#;
+    let puzzle = Puzzle::from_json_str(catalog, Path::new("")).unwrap();
+    assert!(puzzle.solve().unwrap_err().starts_with("Area"));
+}
+
+#[test]
+fn test_report() {
+    let s = r#"{"board": ["..."], "pieces": {"lines": ["1", "aa"]}}"#;
+    let puzzle = Puzzle::from_json_str(s, Path::new("")).unwrap();
+    let report = Report::new(&puzzle.solve());
+    assert!(!report.solved);
+    assert_eq!(
+        serde_json::to_string(&report).unwrap(),
+        r#"{"solved":false,"board":null,"placements":[],"reason":"Area: the minos cover 2 cells but the board has 3 vacant cells"}"#
+    );
+    let s = s.replace("\"aa\"", "\"aaa\"");
+    let puzzle = Puzzle::from_json_str(&s, Path::new("")).unwrap();
+    let report = Report::new(&puzzle.solve());
+    assert_eq!(report.board, Some(vec!["aaa".to_string()]));
+    assert_eq!(
+        serde_json::to_string(&report.placements).unwrap(),
+        r#"[["a",{"x":0,"y":0,"rotation":"Neutral","flipped":false}]]"#
+    );
+}
@@ -0,0 +1,177 @@
+use crate::cover::Supply;
+use crate::polyomino::Equivalence;
+use crate::{Mino, Orientation, Shape, flip_grid, rotate_grid};
+use tracing::warn;
+
+impl Shape {
+    /// A trimmed copy, see [`Shape::trim`].
+    pub fn trimmed(&self) -> Self {
+        let mut shape = self.clone();
+        shape.trim();
+        shape
+    }
+    /// The shape turned like a mino in `orientation`: mirrored first if flipped, then rotated.
+    pub fn oriented(&self, orientation: &Orientation) -> Self {
+        if orientation.flipped {
+            Self(rotate_grid(&flip_grid(&self.0), &orientation.rotation))
+        } else {
+            Self(rotate_grid(&self.0, &orientation.rotation))
+        }
+    }
+    /// The least trimmed orientation of the shape under the symmetries of `equivalence`,
+    /// so that equivalent shapes have equal (and equally hashed) canonical forms.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::Shape;
+    /// use tiling_mino_solver::polyomino::Equivalence;
+    /// let s = Shape::from_str(".##\n##.").unwrap();
+    /// let z = Shape::from_str("...\n##.\n.##").unwrap();
+    /// assert_eq!(s.canonical(Equivalence::Free), z.canonical(Equivalence::Free));
+    /// assert_ne!(s.canonical(Equivalence::OneSided), z.canonical(Equivalence::OneSided));
+    /// ```
+    pub fn canonical(&self, equivalence: Equivalence) -> Self {
+        let trimmed = self.trimmed();
+        let orientations = match equivalence {
+            Equivalence::Free => Orientation::all(),
+            Equivalence::OneSided => Orientation::rotations(),
+            Equivalence::Fixed => return trimmed,
+        };
+        orientations
+            .iter()
+            .map(|orientation| trimmed.oriented(orientation))
+            .min()
+            .unwrap_or(trimmed)
+    }
+    /// Whether the shapes are the same piece under `equivalence`.
+    pub fn equivalent(&self, other: &Self, equivalence: Equivalence) -> bool {
+        self.canonical(equivalence) == other.canonical(equivalence)
+    }
+    /// The orientations of the 8 symmetries of the square which leave the shape unchanged,
+    /// up to translation. Always contains the neutral orientation.
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    /// use tiling_mino_solver::Shape;
+    /// assert_eq!(Shape::from_str("##\n##").unwrap().symmetries().len(), 8);
+    /// assert_eq!(Shape::from_str("###").unwrap().symmetries().len(), 4);
+    /// assert_eq!(Shape::from_str("##.\n.##").unwrap().symmetries().len(), 2);
+    /// assert_eq!(Shape::from_str("#..\n###").unwrap().symmetries().len(), 1);
+    /// ```
+    pub fn symmetries(&self) -> Vec<Orientation> {
+        let trimmed = self.trimmed();
+        Orientation::all()
+            .into_iter()
+            .filter(|orientation| trimmed.oriented(orientation) == trimmed)
+            .collect()
+    }
+}
+
+/// How a mino's shape may be turned: free if it may be flipped, otherwise one-sided.
+fn equivalence(mino: &Mino) -> Equivalence {
+    if mino.orientations().iter().any(|o| o.flipped) {
+        Equivalence::Free
+    } else {
+        Equivalence::OneSided
+    }
+}
+
+/// Pairs of names of minos with different names but the same shape,
+/// up to the orientations both of them may be put in.
+///
+/// ```
+/// use std::str::FromStr;
+/// use tiling_mino_solver::{Mino, symmetry::duplicate_shapes};
+/// let minos: Vec<Mino> = ["aa.\n.aa", ".b\nbb\nb.", "cc"]
+///     .iter()
+///     .map(|s| Mino::from_str(s).unwrap())
+///     .collect();
+/// assert_eq!(duplicate_shapes(&minos), vec![('a', 'b')]);
+/// ```
+pub fn duplicate_shapes(minos: &[Mino]) -> Vec<(char, char)> {
+    let mut pairs = vec![];
+    for (i, a) in minos.iter().enumerate() {
+        for b in &minos[i + 1..] {
+            if a.name == b.name || pairs.contains(&(a.name, b.name)) {
+                continue;
+            }
+            let equivalence = match (equivalence(a), equivalence(b)) {
+                (Equivalence::Free, Equivalence::Free) => Equivalence::Free,
+                _ => Equivalence::OneSided,
+            };
+            if a.shape.equivalent(&b.shape, equivalence) {
+                pairs.push((a.name, b.name));
+            }
+        }
+    }
+    pairs
+}
+
+/// Warn about loaded minos with different names but the same shape.
+pub(crate) fn warn_duplicate_shapes(supplies: &[(Mino, Supply)]) {
+    let minos: Vec<Mino> = supplies.iter().map(|(m, _)| m.clone()).collect();
+    for (a, b) in duplicate_shapes(&minos) {
+        warn!("Minos '{}' and '{}' have the same shape", a, b);
+    }
+}
+
+#[test]
+fn test_shape_canonical() {
+    use std::str::FromStr;
+    let l = Shape::from_str("#.\n#.\n##").unwrap();
+    let j = Shape::from_str(".#\n.#\n##").unwrap();
+    for orientation in Orientation::all() {
+        let turned = l.oriented(&orientation);
+        assert!(turned.equivalent(&l, Equivalence::Free));
+        assert_eq!(
+            turned.equivalent(&l, Equivalence::OneSided),
+            !orientation.flipped
+        );
+    }
+    assert!(j.equivalent(&l, Equivalence::Free));
+    assert!(!j.equivalent(&l, Equivalence::OneSided));
+    let padded = Shape::from_str("....\n.#..\n.#..\n.##.").unwrap();
+    assert!(padded.equivalent(&l, Equivalence::Fixed));
+    assert_eq!(
+        l.canonical(Equivalence::Free),
+        Shape::from_str("..#\n###").unwrap()
+    );
+}
+
+#[test]
+fn test_shape_symmetries_of_polyominoes() {
+    use crate::polyomino::polyominoes;
+    // A free pentomino has as many fixed placements as its symmetry group allows.
+    let fixed: usize = polyominoes(5, Equivalence::Free)
+        .iter()
+        .map(|shape| 8 / shape.symmetries().len())
+        .sum();
+    assert_eq!(fixed, 63);
+    let one_sided: usize = polyominoes(5, Equivalence::Free)
+        .iter()
+        .map(|shape| {
+            let reflective = shape.symmetries().iter().any(|o| o.flipped);
+            if reflective { 1 } else { 2 }
+        })
+        .sum();
+    assert_eq!(one_sided, 18);
+}
+
+#[test]
+fn test_duplicate_shapes() {
+    use std::str::FromStr;
+    let flippable = |s: &str| {
+        let mut mino = Mino::from_str(s).unwrap();
+        mino.constraints.orientations = Some(Orientation::all());
+        mino
+    };
+    let s = Mino::from_str(".ss\nss.").unwrap();
+    let z = Mino::from_str("zz.\n.zz").unwrap();
+    assert!(duplicate_shapes(&[s.clone(), z.clone()]).is_empty());
+    assert!(duplicate_shapes(&[flippable(".ss\nss."), z.clone()]).is_empty());
+    assert_eq!(
+        duplicate_shapes(&[flippable(".ss\nss."), flippable("zz.\n.zz")]),
+        vec![('s', 'z')]
+    );
+    assert!(duplicate_shapes(&[z.clone(), z]).is_empty());
+}
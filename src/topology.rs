@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// How the edges of a board are glued together.
+///
+/// Placements may run across a glued edge and continue on the opposite side.
+/// A twisted edge reverses the other axis on the way through, so a piece
+/// leaving the right edge at row `y` comes back on the left at `height - 1 - y`.
+/// Going around such a board turns a piece over, so placements with `x` in
+/// `width..2 * width` are the board once more, mirrored top to bottom.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Topology {
+    /// No edges are glued.
+    #[default]
+    Plane,
+    /// Left and right edges are glued.
+    Cylinder,
+    /// Left and right edges, and top and bottom edges are glued.
+    Torus,
+    /// Left and right edges are glued with a twist.
+    Mobius,
+    /// Left and right edges are glued with a twist, top and bottom are glued.
+    Klein,
+}
+
+impl Topology {
+    pub fn wraps_x(&self) -> bool {
+        !matches!(self, Topology::Plane)
+    }
+    pub fn wraps_y(&self) -> bool {
+        matches!(self, Topology::Torus | Topology::Klein)
+    }
+    pub(crate) fn twists_x(&self) -> bool {
+        matches!(self, Topology::Mobius | Topology::Klein)
+    }
+    /// Map a position which may lie beyond the right or bottom edge back onto a
+    /// `width` x `height` board. `None` if the position falls off an unglued edge.
+    ///
+    /// ```
+    /// use tiling_mino_solver::topology::Topology;
+    /// assert_eq!(Topology::Plane.wrap(4, 1, 4, 3), None);
+    /// assert_eq!(Topology::Cylinder.wrap(4, 1, 4, 3), Some((0, 1)));
+    /// assert_eq!(Topology::Mobius.wrap(4, 0, 4, 3), Some((0, 2)));
+    /// assert_eq!(Topology::Torus.wrap(5, 3, 4, 3), Some((1, 0)));
+    /// ```
+    pub fn wrap(&self, x: usize, y: usize, width: usize, height: usize) -> Option<(usize, usize)> {
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let y = if self.wraps_y() {
+            y % height
+        } else if y < height {
+            y
+        } else {
+            return None;
+        };
+        if x < width {
+            Some((x, y))
+        } else if self.wraps_x() {
+            let twisted = self.twists_x() && (x / width) % 2 == 1;
+            Some((x % width, if twisted { height - 1 - y } else { y }))
+        } else {
+            None
+        }
+    }
+}
+
+impl FromStr for Topology {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "plane" => Ok(Topology::Plane),
+            "cylinder" => Ok(Topology::Cylinder),
+            "torus" => Ok(Topology::Torus),
+            "mobius" | "möbius" => Ok(Topology::Mobius),
+            "klein" => Ok(Topology::Klein),
+            _ => Err(format!("Unknown topology '{}'", s)),
+        }
+    }
+}
+
+#[test]
+fn test_topology_wrap() {
+    assert_eq!(Topology::Plane.wrap(3, 2, 4, 3), Some((3, 2)));
+    assert_eq!(Topology::Plane.wrap(3, 3, 4, 3), None);
+    assert_eq!(Topology::Cylinder.wrap(6, 2, 4, 3), Some((2, 2)));
+    assert_eq!(Topology::Cylinder.wrap(1, 3, 4, 3), None);
+    assert_eq!(Topology::Torus.wrap(1, 4, 4, 3), Some((1, 1)));
+    assert_eq!(Topology::Mobius.wrap(5, 0, 4, 3), Some((1, 2)));
+    assert_eq!(Topology::Mobius.wrap(9, 0, 4, 3), Some((1, 0)));
+    assert_eq!(Topology::Mobius.wrap(1, 3, 4, 3), None);
+    assert_eq!(Topology::Klein.wrap(5, 3, 4, 3), Some((1, 2)));
+}
+
+#[test]
+fn test_topology_from_str() {
+    assert_eq!(Topology::from_str("torus"), Ok(Topology::Torus));
+    assert_eq!(Topology::from_str("Möbius"), Ok(Topology::Mobius));
+    assert!(Topology::from_str("sphere").is_err());
+}
+
+#[test]
+fn test_cylinder_put_mino_across_edge() {
+    use crate::{Board, Mino, Rotation, TransForm};
+    let mut board = Board::from_str("....\n....").unwrap();
+    board.set_topology(Topology::Cylinder);
+    let mino = Mino::from_str("aaa").unwrap();
+    let t = TransForm {
+        x: 3,
+        y: 0,
+        rotation: Rotation::Neutral,
+        flipped: false,
+    };
+    assert!(board.can_put(&mino, &t));
+    board.put_mino(mino, t);
+    assert_eq!(board.pretty_shape(), "aa.a\n....");
+}
+
+#[test]
+fn test_mobius_put_mino_across_edge() {
+    use crate::{Board, Mino, Rotation, TransForm};
+    let mut board = Board::from_str("...\n...").unwrap();
+    board.set_topology(Topology::Mobius);
+    let mino = Mino::from_str("aa").unwrap();
+    let t = TransForm {
+        x: 2,
+        y: 0,
+        rotation: Rotation::Neutral,
+        flipped: false,
+    };
+    board.put_mino(mino, t);
+    assert_eq!(board.pretty_shape(), "..a\na..");
+}
+
+#[test]
+fn test_tile_only_with_wraparound() {
+    use crate::{Board, Mino};
+    let minos = vec![Mino::from_str("aa").unwrap()];
+    let mut board = Board::from_str(".##.").unwrap();
+    assert!(board.tile_serial(&minos).is_none());
+    board.set_topology(Topology::Cylinder);
+    let tiled = board.tile_serial(&minos).unwrap();
+    assert_eq!(tiled.pretty_shape(), "a##a");
+}
+
+#[test]
+fn test_torus_rejects_self_overlap() {
+    use crate::{Board, Mino};
+    let mut board = Board::from_str("..\n..").unwrap();
+    board.set_topology(Topology::Torus);
+    assert!(
+        board
+            .search_can_put(&Mino::from_str("aaa").unwrap())
+            .is_empty()
+    );
+    assert_eq!(
+        board.search_can_put(&Mino::from_str("aa").unwrap()).len(),
+        16
+    );
+}
+
+#[test]
+fn test_mobius_one_sided_mino_crosses_from_left() {
+    use crate::{Board, Mino, Orientation};
+    use std::collections::BTreeSet;
+    let mut board = Board::from_str("....\n....\n....").unwrap();
+    board.set_topology(Topology::Mobius);
+    let l = Mino::from_str("a.\na.\naa").unwrap();
+    let free = Mino {
+        constraints: crate::constraints::Constraints {
+            orientations: Some(Orientation::all()),
+            ..Default::default()
+        },
+        ..l.clone()
+    };
+    let cells = |mino: &Mino| -> BTreeSet<Vec<(usize, usize)>> {
+        board
+            .search_can_put(mino)
+            .iter()
+            .map(|t| {
+                let mut cells = board
+                    .covered_cells(&mino.oriented(&t.orientation()), t)
+                    .unwrap();
+                cells.sort();
+                cells
+            })
+            .collect()
+    };
+    // Going around a Möbius strip turns a piece over, so a one-sided L reaches
+    // every placement of a free one, some of them only from the left.
+    let one_sided = cells(&l);
+    assert_eq!(one_sided, cells(&free));
+    let from_left = board
+        .search_can_put(&l)
+        .into_iter()
+        .filter(|t| t.x >= board.width())
+        .count();
+    assert!(from_left > 0);
+    assert_eq!(board.search_can_put(&l).len(), one_sided.len());
+}
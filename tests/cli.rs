@@ -0,0 +1,41 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Run the solver with `args` and `stdin`, returning what it wrote to stdout.
+fn run(args: &[&str], stdin: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tiling-mino-solver"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// More than 8 minos, which the search logs progress for.
+const NINE_MONOMINOES: &str = r#"{"board": ["........."], "pieces": {"lines": ["9", "a"]}}"#;
+
+#[test]
+fn test_json_output_is_one_line() {
+    let stdout = run(&["-", "--json"], NINE_MONOMINOES);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 1, "{}", stdout);
+    let report: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(report["solved"], true);
+}
+
+#[test]
+fn test_progress_is_not_on_stdout() {
+    let stdout = run(&["-"], NINE_MONOMINOES);
+    assert_eq!(stdout.lines().count(), 1, "{}", stdout);
+    assert!(stdout.contains("aaaaaaaaa"));
+}